@@ -0,0 +1,57 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// The scaling pass's shader and the `SCALAR` type of each SPIR-V module compiled from it
+const SHADER: &str = "shaders/scale.comp";
+const MODULES: [(&str, &str); 2] = [("float", "scale_f32.spv"), ("double", "scale_f64.spv")];
+
+fn main() {
+  if let Err(e) = run() {
+    eprintln!("Failed to build vkfft: {}", e);
+    std::process::exit(1);
+  }
+}
+
+/// Compile the scaling pass's shader with the glslangValidator of the glslang vkfft-sys links
+fn run() -> Result<(), Box<dyn Error>> {
+  println!("cargo:rerun-if-changed={}", SHADER);
+  println!("cargo:rerun-if-changed=build.rs");
+
+  let validator = std::env::var("DEP_VKFFT_GLSLANG_VALIDATOR")
+    .map_err(|_| "vkfft-sys didn't report a glslangValidator to compile the shaders with")?;
+  let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+
+  for (scalar, module) in MODULES.iter() {
+    let output = Command::new(&validator)
+      .arg("-V")
+      .arg(format!("-DSCALAR={}", scalar))
+      .arg("-o")
+      .arg(out_dir.join(module))
+      .arg(SHADER)
+      .output()
+      .map_err(|e| {
+        format!(
+          "Failed to run {} to compile {}: {}. Build glslang with its binaries or put \
+           glslangValidator on the PATH",
+          validator, SHADER, e
+        )
+      })?;
+
+    if !output.status.success() {
+      return Err(
+        format!(
+          "{} failed to compile {} with SCALAR={}:\n{}{}",
+          validator,
+          SHADER,
+          scalar,
+          String::from_utf8_lossy(&output.stdout),
+          String::from_utf8_lossy(&output.stderr)
+        )
+        .into(),
+      );
+    }
+  }
+
+  Ok(())
+}
@@ -5,7 +5,9 @@ use vulkano::instance::debug::{DebugCallback, Message, MessageSeverity, MessageT
 use vulkano::instance::{Instance, PhysicalDevice};
 use vulkano::sync::Fence;
 
-use std::{error::Error, fmt::{Display, Formatter}, sync::Arc};
+use vkfft::error::{Error, Result, VulkanError};
+
+use std::{fmt::{Display, Formatter}, sync::Arc};
 
 const MESSAGE_SEVERITIES: MessageSeverity = MessageSeverity {
   error: true,
@@ -61,7 +63,7 @@ pub struct Context<'a> {
 }
 
 impl<'a> Context<'a> {
-  pub fn new(instance: &'a Arc<Instance>) -> Result<Self, Box<dyn std::error::Error>> {
+  pub fn new(instance: &'a Arc<Instance>) -> Result<Self> {
     let debug_cb = DebugCallback::new(
       &instance,
       MESSAGE_SEVERITIES,
@@ -72,14 +74,14 @@ impl<'a> Context<'a> {
 
     let physical = PhysicalDevice::enumerate(&instance)
       .next()
-      .ok_or("No device available")?;
+      .ok_or(Error::Vulkan(VulkanError::NoPhysicalDevice))?;
 
     println!("Using {}", physical.name());
 
     let queue_family = physical
       .queue_families()
       .find(|&q| q.supports_compute() && q.supports_graphics())
-      .ok_or("Couldn't find a compute queue family")?;
+      .ok_or(Error::Vulkan(VulkanError::NoQueueFamily))?;
 
     let (device, mut queues) = Device::new(
       physical,
@@ -109,10 +111,7 @@ impl<'a> Context<'a> {
     })
   }
 
-  pub fn submit(
-    &mut self,
-    command_buffer: UnsafeCommandBuffer,
-  ) -> Result<(), Box<dyn std::error::Error>> {
+  pub fn submit(&mut self, command_buffer: UnsafeCommandBuffer) -> Result<()> {
     unsafe {
       let mut submit = SubmitCommandBufferBuilder::new();
       submit.add_command_buffer(&command_buffer);
@@ -128,24 +127,21 @@ impl<'a> Context<'a> {
     Ok(())
   }
 
-  pub fn alloc_cmd_buffer(
-    &self,
-    secondary: bool,
-  ) -> Result<UnsafeCommandPoolAlloc, Box<dyn Error>> {
+  pub fn alloc_cmd_buffer(&self, secondary: bool) -> Result<UnsafeCommandPoolAlloc> {
     Ok(
       self
         .pool
         .alloc_command_buffers(secondary, 1)?
         .next()
-        .ok_or("Failed to allocate cmd buffer")?,
+        .ok_or(Error::Vulkan(VulkanError::NoCommandBuffer))?,
     )
   }
 
-  pub fn alloc_primary_cmd_buffer(&self) -> Result<UnsafeCommandPoolAlloc, Box<dyn Error>> {
+  pub fn alloc_primary_cmd_buffer(&self) -> Result<UnsafeCommandPoolAlloc> {
     self.alloc_cmd_buffer(false)
   }
 
-  pub fn alloc_secondary_cmd_buffer(&self) -> Result<UnsafeCommandPoolAlloc, Box<dyn Error>> {
+  pub fn alloc_secondary_cmd_buffer(&self) -> Result<UnsafeCommandPoolAlloc> {
     self.alloc_cmd_buffer(true)
   }
 }
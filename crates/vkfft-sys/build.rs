@@ -31,9 +31,7 @@ where
     build.flag(format!("-l{}", library.as_ref()).as_str());
   }
 
-  build
-    .cargo_metadata(true)
-    .static_flag(true);
+  build.cargo_metadata(true);
   
   for (key, value) in defines.iter() {
     build.define(*key, Some(*value));
@@ -70,12 +68,7 @@ where
     .chain(defines.iter())
     .chain(include_dirs.iter());
 
-  println!("{:?}", clang_args);
-
-  
-
-
-  let res = bindgen::Builder::default()
+  let builder = bindgen::Builder::default()
     .clang_args(clang_args)
     .parse_callbacks(Box::new(bindgen::CargoCallbacks))
     .header(file.as_ref().to_str().unwrap())
@@ -94,8 +87,9 @@ where
     .allowlist_function("initializeVkFFT")
     .allowlist_function("deleteVkFFT")
     .allowlist_function("VkFFTGetVersion")
-    
-    .generate();
+    .allowlist_var("VKFFT_MAX_FFT_DIMENSIONS");
+
+  let res = builder.generate();
   
   let bindings = match res {
     Ok(x) => x,
@@ -108,27 +102,257 @@ where
   Ok(bindings)
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-  let vkfft_root = std::env::var("VKFFT_ROOT")?;
+/// List every `VkFFTResult_*` constant of the bindings, so the crate can check it maps them all
+fn gen_results(bindings: &str) -> String {
+  // Unformatted bindings may be a single line, so look for the declarations anywhere
+  let names: Vec<&str> = bindings
+    .split("pub const ")
+    .skip(1)
+    .filter_map(|decl| {
+      decl
+        .trim_start()
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .next()
+    })
+    .filter(|name| name.starts_with("VkFFTResult_"))
+    .collect();
+
+  let mut res = String::from("/// Name and value of every `VkFFTResult` code in the header\n");
+  res.push_str("pub const VKFFT_RESULTS: &[(&str, VkFFTResult)] = &[\n");
+
+  for name in names {
+    res.push_str(&format!("  (\"{}\", {}),\n", name, name));
+  }
+
+  res.push_str("];\n");
+  res
+}
+
+/// Find the packed version of the header, e.g. `10221` for 1.2.21, from a `VKFFT_VERSION` macro
+/// or else the value `VkFFTGetVersion` returns
+fn header_version(header: &str) -> Option<u32> {
+  let parse = |value: &str| -> Option<u32> {
+    let digits: String = value.trim_start().chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+  };
+
+  for line in header.lines() {
+    let mut tokens = line.split_whitespace();
+    if let (Some("#define"), Some("VKFFT_VERSION"), Some(value)) = (tokens.next(), tokens.next(), tokens.next()) {
+      if let Some(version) = parse(value) {
+        return Some(version);
+      }
+    }
+  }
+
+  for (start, _) in header.match_indices("VkFFTGetVersion") {
+    let rest = &header[start..];
+
+    // Skip declarations and calls, the definition's body comes before any semicolon
+    let body = match (rest.find('{'), rest.find(';')) {
+      (Some(open), Some(semicolon)) if open < semicolon => &rest[open..],
+      _ => continue,
+    };
+
+    if let Some(version) = body.find("return").and_then(|pos| parse(&body[pos + "return".len()..])) {
+      return Some(version);
+    }
+  }
+
+  None
+}
+
+/// Where the VkFFT header, the glslang headers and the glslang libraries to link come from
+struct Sources {
+  vkfft_include: PathBuf,
+  glslang_include: PathBuf,
+  library_dirs: Vec<PathBuf>,
+  libraries: &'static [&'static str],
+  /// glslangValidator of the same glslang, which the vkfft crate compiles its own shaders with
+  glslang_validator: PathBuf,
+}
+
+/// Libraries to link with a VkFFT checkout, from the glslang build VkFFT's CMake project makes
+/// in `build/glslang-main` except for the system's Vulkan loader
+#[cfg(not(feature = "vendored"))]
+const LIBRARIES: [&str; 7] = [
+  "glslang",
+  "MachineIndependent",
+  "OSDependent",
+  "GenericCodeGen",
+  "OGLCompiler",
+  "vulkan",
+  "SPIRV"
+];
+
+/// VkFFT release the `vendored` feature uses, and its version as `VkFFTGetVersion` packs it
+#[cfg(feature = "vendored")]
+const VKFFT_TAG: &str = "v1.2.21";
+#[cfg(feature = "vendored")]
+const VKFFT_VERSION: u32 = 10221;
+
+/// glslang release the `vendored` feature builds, and the libraries to link with it: the static
+/// libraries it installs with HLSL and the SPIR-V optimizer disabled, and the system's Vulkan
+/// loader. Later releases merged or removed some of them.
+#[cfg(feature = "vendored")]
+const GLSLANG_TAG: &str = "11.8.0";
+#[cfg(feature = "vendored")]
+const GLSLANG_LIBRARIES: [&str; 7] = [
+  "glslang",
+  "MachineIndependent",
+  "OSDependent",
+  "GenericCodeGen",
+  "OGLCompiler",
+  "vulkan",
+  "SPIRV"
+];
+
+fn require<P: AsRef<Path>>(path: P, what: &str, hint: &str) -> Result<(), Box<dyn Error>> {
+  if path.as_ref().exists() {
+    return Ok(());
+  }
+
+  Err(format!("{} not found at {}. {}", what, path.as_ref().display(), hint).into())
+}
+
+/// Use a VkFFT checkout built with CMake, as described in the README
+#[cfg(not(feature = "vendored"))]
+fn sources() -> Result<Sources, Box<dyn Error>> {
+  let vkfft_root = match std::env::var("VKFFT_ROOT") {
+    Ok(root) if !root.is_empty() => PathBuf::from(root),
+    Ok(_) | Err(std::env::VarError::NotPresent) => {
+      return Err(
+        "VKFFT_ROOT is not set. Set it to a VkFFT checkout built with CMake in its `build` \
+         directory, or enable the `vendored` feature to build the pinned VkFFT and glslang \
+         releases bundled in vkfft-sys/vendor"
+          .into(),
+      )
+    }
+    Err(std::env::VarError::NotUnicode(root)) => {
+      return Err(format!("VKFFT_ROOT is not valid unicode: {:?}", root).into())
+    }
+  };
+
+  let hint = "VKFFT_ROOT must point at a VkFFT checkout built with `mkdir build && cd build && \
+              cmake .. && make`";
+  let build = vkfft_root.join("build/glslang-main");
+
+  require(vkfft_root.join("vkFFT/vkFFT.h"), "vkFFT.h", hint)?;
+  require(vkfft_root.join("glslang-main/glslang/Include"), "the glslang headers", hint)?;
+  require(&build, "the glslang build directory", hint)?;
+
+  let library_dirs = vec![
+    build.join("glslang"),
+    build.join("glslang/OSDependent/Unix"),
+    build.join("glslang/OGLCompilersDLL"),
+    build.join("SPIRV"),
+  ];
+
+  // The Vulkan loader comes from the system, everything else from the glslang build
+  for library in LIBRARIES.iter().filter(|library| **library != "vulkan") {
+    let file = format!("lib{}.a", library);
+    if !library_dirs.iter().any(|dir| dir.join(&file).exists()) {
+      return Err(format!("{} not found under {}. {}", file, build.display(), hint).into());
+    }
+  }
+
+  // glslang builds its binaries by default, otherwise look for one on the PATH
+  let glslang_validator = match build.join("StandAlone/glslangValidator") {
+    validator if validator.exists() => validator,
+    _ => PathBuf::from("glslangValidator"),
+  };
+
+  Ok(Sources {
+    vkfft_include: vkfft_root.join("vkFFT"),
+    glslang_include: vkfft_root.join("glslang-main/glslang/Include"),
+    library_dirs,
+    libraries: &LIBRARIES,
+    glslang_validator,
+  })
+}
+
+/// Build glslang from `vendor/glslang` and use the VkFFT header in `vendor/VkFFT`, the pinned
+/// releases bundled as git submodules
+#[cfg(feature = "vendored")]
+fn sources() -> Result<Sources, Box<dyn Error>> {
+  let vendor = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR")?).join("vendor");
+  let hint = "The `vendored` feature builds the VkFFT and glslang releases bundled with vkfft-sys \
+              as git submodules of vkfft-rs. In a git checkout, run `git submodule update --init` \
+              to check them out at the pinned commits";
+
+  let header = vendor.join("VkFFT/vkFFT/vkFFT.h");
+  let changes = vendor.join("glslang/CHANGES.md");
+  require(&header, "vkFFT.h", hint)?;
+  require(vendor.join("glslang/CMakeLists.txt"), "the glslang sources", hint)?;
+  require(&changes, "the glslang change log", hint)?;
+
+  println!("cargo:rerun-if-changed={}", vendor.display());
+
+  let header = std::fs::read_to_string(&header)?;
+  if header_version(&header) != Some(VKFFT_VERSION) {
+    return Err(format!("vendor/VkFFT is not VkFFT {}. {}", VKFFT_TAG, hint).into());
+  }
+
+  // The latest release heads the change log as `## <version> <date>`
+  let changes = std::fs::read_to_string(&changes)?;
+  let release = changes
+    .lines()
+    .find_map(|line| line.strip_prefix("## "))
+    .and_then(|line| line.split_whitespace().next());
+  if release != Some(GLSLANG_TAG) {
+    return Err(format!("vendor/glslang is not glslang {}. {}", GLSLANG_TAG, hint).into());
+  }
+
+  let dst = cmake::Config::new(vendor.join("glslang"))
+    .define("BUILD_SHARED_LIBS", "OFF")
+    .define("BUILD_TESTING", "OFF")
+    .define("ENABLE_CTEST", "OFF")
+    .define("ENABLE_GLSLANG_BINARIES", "ON")
+    .define("ENABLE_HLSL", "OFF")
+    .define("ENABLE_OPT", "OFF")
+    // Some distributions default to lib64
+    .define("CMAKE_INSTALL_LIBDIR", "lib")
+    .build();
+
+  let lib = dst.join("lib");
+  let hint = "The glslang build installed a different set of libraries than expected";
+  for library in GLSLANG_LIBRARIES.iter().filter(|library| **library != "vulkan") {
+    require(lib.join(format!("lib{}.a", library)), library, hint)?;
+  }
+
+  let glslang_validator = dst.join("bin/glslangValidator");
+  require(&glslang_validator, "glslangValidator", "The glslang build didn't install its binaries")?;
+
+  Ok(Sources {
+    vkfft_include: vendor.join("VkFFT/vkFFT"),
+    glslang_include: dst.join("include/glslang/Include"),
+    library_dirs: vec![lib],
+    libraries: &GLSLANG_LIBRARIES,
+    glslang_validator,
+  })
+}
+
+fn main() {
+  if let Err(e) = run() {
+    eprintln!("Failed to build vkfft-sys: {}", e);
+    std::process::exit(1);
+  }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+  println!("cargo:rerun-if-env-changed=VKFFT_ROOT");
+
+  let sources = sources()?;
   let out_dir = std::env::var("OUT_DIR")?;
   let out_dir = PathBuf::from(out_dir);
 
-  let library_dirs = [
-    format!("{}/build/glslang-main/glslang", vkfft_root),
-    format!("{}/build/glslang-main/glslang/OSDependent/Unix", vkfft_root),
-    format!("{}/build/glslang-main/glslang/OGLCompilersDLL", vkfft_root),
-    format!("{}/build/glslang-main/SPIRV", vkfft_root),
-  ];
+  let library_dirs: Vec<String> = sources
+    .library_dirs
+    .iter()
+    .map(|dir| dir.display().to_string())
+    .collect();
 
-  let libraries = [
-    "glslang",
-    "MachineIndependent",
-    "OSDependent",
-    "GenericCodeGen",
-    "OGLCompiler",
-    "vulkan",
-    "SPIRV"
-  ];
+  let libraries = sources.libraries;
 
   for library_dir in library_dirs.iter() {
     println!("cargo:rustc-link-search={}", library_dir);
@@ -138,13 +362,16 @@ fn main() -> Result<(), Box<dyn Error>> {
     println!("cargo:rustc-link-lib={}", library);
   }
 
+  // Read by the vkfft build script as DEP_VKFFT_GLSLANG_VALIDATOR
+  println!("cargo:glslang_validator={}", sources.glslang_validator.display());
+
 
   println!("cargo:rerun-if-changed=wrapper.cpp");
   println!("cargo:rerun-if-changed=build.rs");
 
   let include_dirs = [
-    format!("{}/vkFFT", &vkfft_root),
-    format!("{}/glslang-main/glslang/Include", vkfft_root)
+    sources.vkfft_include.display().to_string(),
+    sources.glslang_include.display().to_string(),
   ];
 
   let defines = [
@@ -152,7 +379,9 @@ fn main() -> Result<(), Box<dyn Error>> {
     ("VK_API_VERSION", "11")
   ];
 
-  let wrapper = std::fs::read_to_string(format!("{}/vkFFT/vkFFT.h", vkfft_root))?
+  let header = sources.vkfft_include.join("vkFFT.h");
+  let wrapper = std::fs::read_to_string(&header)
+    .map_err(|e| format!("Failed to read {}: {}", header.display(), e))?
     .replace("static inline", "");
 
   let rw = out_dir.join("vkfft_rw.hpp");
@@ -160,8 +389,33 @@ fn main() -> Result<(), Box<dyn Error>> {
 
   build_lib(&out_dir, library_dirs.iter(), libraries.iter(), &defines, &include_dirs)?;
 
-  let bindings = gen_wrapper(&rw, &defines, &include_dirs)?;
-  bindings.write_to_file(out_dir.join("bindings.rs"))?;
+  let mut bindings = gen_wrapper(&rw, &defines, &include_dirs)?.to_string();
+
+  // Headers before VkFFT 1.3 hardcode three dimensions instead of defining the limit
+  if !bindings.contains("const VKFFT_MAX_FFT_DIMENSIONS:") {
+    bindings.push_str("\npub const VKFFT_MAX_FFT_DIMENSIONS: u32 = 3;\n");
+  }
+
+  std::fs::write(out_dir.join("bindings.rs"), &bindings)?;
+  std::fs::write(out_dir.join("results.rs"), gen_results(&bindings))?;
+
+  let version = match header_version(&wrapper) {
+    Some(version) => format!("Some({})", version),
+    None => {
+      println!(
+        "cargo:warning=Couldn't find the version of {}, vkfft::header_version will return None",
+        header.display()
+      );
+      "None".to_string()
+    }
+  };
+  std::fs::write(
+    out_dir.join("version.rs"),
+    format!(
+      "/// Packed version of the header the bindings were generated from, if it could be found\npub const VKFFT_HEADER_VERSION: Option<u32> = {};\n",
+      version
+    ),
+  )?;
   
   Ok(())
 }
@@ -3,3 +3,5 @@
 #![allow(non_snake_case)]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+include!(concat!(env!("OUT_DIR"), "/results.rs"));
+include!(concat!(env!("OUT_DIR"), "/version.rs"));
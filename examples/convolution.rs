@@ -1,6 +1,7 @@
 use vkfft::app::App;
 use vkfft::app::LaunchParams;
 use vkfft::config::Config;
+use vkfft::error::Result;
 
 use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
 use vulkano::command_buffer::{
@@ -10,7 +11,7 @@ use vulkano::command_buffer::{
 
 use vulkano::instance::{Instance, InstanceExtensions};
 
-use std::{error::Error, sync::Arc};
+use std::sync::Arc;
 
 use util::{Context, SizeIterator, MatrixFormatter};
 
@@ -31,7 +32,7 @@ pub fn transform_kernel(
   batch_count: u32,
   size: &[u32; 2],
   kernel: &Arc<CpuAccessibleBuffer<[f32]>>,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<()> {
   // Configure kernel FFT
   let config = Config::builder()
     .physical_device(context.physical)
@@ -46,7 +47,7 @@ pub fn transform_kernel(
     .batch_count(1)
     .r2c()
     .disable_reorder_four_step()
-    .dim(&size)
+    .dim(&size.map(u64::from))
     .build()?;
 
   // Allocate a command buffer
@@ -78,22 +79,39 @@ pub fn convolve(
   coordinate_features: u32,
   size: &[u32; 2],
   kernel: &Arc<CpuAccessibleBuffer<[f32]>>,
-) -> Result<(), Box<dyn Error>> {
-  let input_buffer_size = coordinate_features * 2 * (size[0] / 2 + 1) * size[1];
-  let buffer_size = coordinate_features * 2 * (size[0] / 2 + 1) * size[1];
+) -> Result<()> {
+  // Configure convolution FFT
+  let conv_config = Config::builder()
+    .physical_device(context.physical)
+    .device(context.device.clone())
+    .fence(&context.fence)
+    .queue(context.queue.clone())
+    .command_pool(context.pool.clone())
+    .convolution()
+    .kernel(kernel.clone())
+    .normalize()
+    .coordinate_features(coordinate_features)
+    .batch_count(1)
+    .r2c()
+    .disable_reorder_four_step()
+    .input_formatted(true)
+    .dim(&size.map(u64::from));
+
+  // Let the configuration compute the R2C padded buffer sizes
+  let buffer_sizes = conv_config.buffer_sizes();
 
   let input_buffer = CpuAccessibleBuffer::from_iter(
     context.device.clone(),
     DEFAULT_BUFFER_USAGE,
     false,
-    (0..input_buffer_size).map(|_| 0.0f32),
+    (0..buffer_sizes.input_buffer as usize / std::mem::size_of::<f32>()).map(|_| 0.0f32),
   )?;
 
   let buffer = CpuAccessibleBuffer::from_iter(
     context.device.clone(),
     DEFAULT_BUFFER_USAGE,
     false,
-    (0..buffer_size).map(|_| 0.0f32),
+    (0..buffer_sizes.buffer as usize / std::mem::size_of::<f32>()).map(|_| 0.0f32),
   )?;
 
   {
@@ -111,24 +129,9 @@ pub fn convolve(
   println!("{}", MatrixFormatter::new(size, &input_buffer));
   println!();
 
-  // Configure kernel FFT
-  let conv_config = Config::builder()
-    .physical_device(context.physical)
-    .device(context.device.clone())
-    .fence(&context.fence)
-    .queue(context.queue.clone())
+  let conv_config = conv_config
     .input_buffer(input_buffer)
     .buffer(buffer.clone())
-    .command_pool(context.pool.clone())
-    .convolution()
-    .kernel(kernel.clone())
-    .normalize()
-    .coordinate_features(coordinate_features)
-    .batch_count(1)
-    .r2c()
-    .disable_reorder_four_step()
-    .input_formatted(true)
-    .dim(&size)
     .build()?;
 
   // Allocate a command buffer
@@ -158,7 +161,7 @@ pub fn convolve(
   Ok(())
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> Result<()> {
   println!("VkFFT version: {}", vkfft::version());
 
   let instance = Instance::new(
@@ -4,14 +4,18 @@ use error::check_error;
 use vulkano::{buffer::BufferAccess, VulkanHandle, VulkanObject};
 
 use crate::{
-  config::{Config, ConfigGuard},
-  error,
+  config::{BufferRole, Config, ConfigGuard, ConvolutionOp, Direction},
+  correlation::{find_shift, Shift, ShiftError},
+  error::{self, Operation},
+  plan::{PlanError, SavedPlan},
+  scale::{Part, ScalePass},
+  shader::ShaderSources,
 };
 
 use std::pin::Pin;
 use vk_sys as vk;
 
-use std::ptr::addr_of_mut;
+use std::{path::Path, ptr::addr_of_mut};
 
 use derive_more::{Display, Error};
 
@@ -32,26 +36,90 @@ pub enum LaunchError {
   ConfigSpecifiesInputBuffer,
   ConfigSpecifiesOutputBuffer,
   ConfigSpecifiesKernel,
+  #[display(
+    fmt = "cannot run the {} transform, the application was planned for {} only",
+    direction,
+    planned
+  )]
+  DirectionNotPlanned {
+    direction: Direction,
+    planned: Direction,
+  },
+  #[display(
+    fmt = "{} is {} bytes but the configuration requires {} bytes",
+    role,
+    size,
+    required
+  )]
+  BufferTooSmall {
+    role: BufferRole,
+    size: u64,
+    required: u64,
+  },
+  #[display(
+    fmt = "offset {} of {} is not a multiple of {} bytes",
+    offset,
+    role,
+    alignment
+  )]
+  MisalignedOffset {
+    role: BufferRole,
+    offset: u64,
+    alignment: u64,
+  },
+  #[display(
+    fmt = "{} was given {} buffers but the configuration has {}",
+    role,
+    count,
+    expected
+  )]
+  BufferCountMismatch {
+    role: BufferRole,
+    count: usize,
+    expected: usize,
+  },
+  #[display(
+    fmt = "part {} of {} has offset {}, but only the first part may",
+    index,
+    role,
+    offset
+  )]
+  OffsetInBufferPart {
+    role: BufferRole,
+    index: usize,
+    offset: u64,
+  },
+  #[display(
+    fmt = "{} is too large to normalize, the scaling pass indexes it with 32-bit integers",
+    role
+  )]
+  NormalizationRangeTooLarge {
+    role: BufferRole,
+  },
+  #[display(
+    fmt = "the inverse returns to the input buffer, but none is configured or given at launch"
+  )]
+  NoInputBuffer,
 }
 
 pub struct LaunchParamsBuilder {
   command_buffer: Option<vk::CommandBuffer>,
-  buffer: Option<Arc<dyn BufferAccess>>,
-  temp_buffer: Option<Arc<dyn BufferAccess>>,
-  input_buffer: Option<Arc<dyn BufferAccess>>,
-  output_buffer: Option<Arc<dyn BufferAccess>>,
-  kernel: Option<Arc<dyn BufferAccess>>,
+  buffer: Vec<Arc<dyn BufferAccess>>,
+  temp_buffer: Vec<Arc<dyn BufferAccess>>,
+  input_buffer: Vec<Arc<dyn BufferAccess>>,
+  output_buffer: Vec<Arc<dyn BufferAccess>>,
+  kernel: Vec<Arc<dyn BufferAccess>>,
 }
 
 impl LaunchParamsBuilder {
   pub fn new() -> Self {
     Self {
-      buffer: None,
+      buffer: Vec::new(),
       command_buffer: None,
-      input_buffer: None,
-      kernel: None,
-      output_buffer: None,
-      temp_buffer: None,
+      input_buffer: Vec::new(),
+      kernel: Vec::new(),
+      output_buffer: Vec::new(),
+      temp_buffer: Vec::new(),
     }
   }
 
@@ -64,27 +132,53 @@ impl LaunchParamsBuilder {
   }
 
   pub fn buffer(mut self, buffer: Arc<dyn BufferAccess>) -> Self {
-    self.buffer = Some(buffer);
+    self.buffer = vec![buffer];
+    self
+  }
+
+  /// Supply `buffer` split across several `VkBuffer`s, one for each configured buffer size
+  pub fn buffers(mut self, buffers: Vec<Arc<dyn BufferAccess>>) -> Self {
+    self.buffer = buffers;
     self
   }
 
   pub fn temp_buffer(mut self, temp_buffer: Arc<dyn BufferAccess>) -> Self {
-    self.temp_buffer = Some(temp_buffer);
+    self.temp_buffer = vec![temp_buffer];
+    self
+  }
+
+  pub fn temp_buffers(mut self, temp_buffers: Vec<Arc<dyn BufferAccess>>) -> Self {
+    self.temp_buffer = temp_buffers;
     self
   }
 
   pub fn input_buffer(mut self, input_buffer: Arc<dyn BufferAccess>) -> Self {
-    self.input_buffer = Some(input_buffer);
+    self.input_buffer = vec![input_buffer];
+    self
+  }
+
+  pub fn input_buffers(mut self, input_buffers: Vec<Arc<dyn BufferAccess>>) -> Self {
+    self.input_buffer = input_buffers;
     self
   }
 
   pub fn output_buffer(mut self, output_buffer: Arc<dyn BufferAccess>) -> Self {
-    self.output_buffer = Some(output_buffer);
+    self.output_buffer = vec![output_buffer];
+    self
+  }
+
+  pub fn output_buffers(mut self, output_buffers: Vec<Arc<dyn BufferAccess>>) -> Self {
+    self.output_buffer = output_buffers;
     self
   }
 
   pub fn kernel(mut self, kernel: Arc<dyn BufferAccess>) -> Self {
-    self.kernel = Some(kernel);
+    self.kernel = vec![kernel];
+    self
+  }
+
+  pub fn kernels(mut self, kernels: Vec<Arc<dyn BufferAccess>>) -> Self {
+    self.kernel = kernels;
     self
   }
 
@@ -109,64 +203,163 @@ impl LaunchParamsBuilder {
 pub(crate) struct LaunchParamsGuard {
   pub(crate) params: vkfft_sys::VkFFTLaunchParams,
   pub(crate) command_buffer: vk_sys::CommandBuffer,
-  pub(crate) buffer: Option<vk_sys::Buffer>,
-  pub(crate) temp_buffer: Option<vk_sys::Buffer>,
-  pub(crate) input_buffer: Option<vk_sys::Buffer>,
-  pub(crate) output_buffer: Option<vk_sys::Buffer>,
-  pub(crate) kernel: Option<vk_sys::Buffer>,
+  pub(crate) buffer: Vec<vk_sys::Buffer>,
+  pub(crate) temp_buffer: Vec<vk_sys::Buffer>,
+  pub(crate) input_buffer: Vec<vk_sys::Buffer>,
+  pub(crate) output_buffer: Vec<vk_sys::Buffer>,
+  pub(crate) kernel: Vec<vk_sys::Buffer>,
 }
 
+/// Buffers to use for a single launch. An empty list means the buffer of that role is taken from
+/// the configuration.
 pub struct LaunchParams {
   pub command_buffer: vk::CommandBuffer,
-  pub buffer: Option<Arc<dyn BufferAccess>>,
-  pub temp_buffer: Option<Arc<dyn BufferAccess>>,
-  pub input_buffer: Option<Arc<dyn BufferAccess>>,
-  pub output_buffer: Option<Arc<dyn BufferAccess>>,
-  pub kernel: Option<Arc<dyn BufferAccess>>,
+  pub buffer: Vec<Arc<dyn BufferAccess>>,
+  pub temp_buffer: Vec<Arc<dyn BufferAccess>>,
+  pub input_buffer: Vec<Arc<dyn BufferAccess>>,
+  pub output_buffer: Vec<Arc<dyn BufferAccess>>,
+  pub kernel: Vec<Arc<dyn BufferAccess>>,
 }
 
 impl LaunchParams {
-  fn buffer_object<B>(buffer: B) -> u64
-  where
-    B: AsRef<dyn BufferAccess>,
-  {
-    buffer.as_ref().inner().buffer.internal_object().value()
+  fn buffer_objects(buffers: &[Arc<dyn BufferAccess>]) -> Vec<u64> {
+    buffers
+      .iter()
+      .map(|b| b.inner().buffer.internal_object().value())
+      .collect()
+  }
+
+  /// Offset of the first buffer of a role, falling back to the configured one
+  fn buffer_offset(
+    buffers: &[Arc<dyn BufferAccess>],
+    config: &ConfigGuard,
+    role: BufferRole,
+  ) -> u64 {
+    match buffers.first() {
+      Some(b) => b.inner().offset as u64,
+      None => config.buffer_guard(role).offset,
+    }
+  }
+
+  fn buffers(&self) -> [(BufferRole, &[Arc<dyn BufferAccess>]); 5] {
+    [
+      (BufferRole::Buffer, &self.buffer),
+      (BufferRole::InputBuffer, &self.input_buffer),
+      (BufferRole::OutputBuffer, &self.output_buffer),
+      (BufferRole::TempBuffer, &self.temp_buffer),
+      (BufferRole::Kernel, &self.kernel),
+    ]
+  }
+
+  /// Check buffers supplied at launch against the sizes and alignment the configuration expects
+  pub(crate) fn validate(&self, config: &ConfigGuard) -> Result<(), LaunchError> {
+    for (role, buffers) in self.buffers().iter() {
+      let role = *role;
+
+      if buffers.is_empty() {
+        continue;
+      }
+
+      let configured = &config.buffer_guard(role).sizes;
+      if configured.is_empty() {
+        // Without configured sizes VkFFT treats the buffers as one of the layout's size
+        let size = buffers.iter().map(|b| b.size() as u64).sum();
+        let required = config.layout.buffer_sizes().get(role);
+        if size < required {
+          return Err(LaunchError::BufferTooSmall {
+            role,
+            size,
+            required,
+          });
+        }
+      } else {
+        if buffers.len() != configured.len() {
+          return Err(LaunchError::BufferCountMismatch {
+            role,
+            count: buffers.len(),
+            expected: configured.len(),
+          });
+        }
+
+        for (buffer, &required) in buffers.iter().zip(configured.iter()) {
+          if (buffer.size() as u64) < required {
+            return Err(LaunchError::BufferTooSmall {
+              role,
+              size: buffer.size() as u64,
+              required,
+            });
+          }
+        }
+      }
+
+      let offset = buffers[0].inner().offset as u64;
+      let alignment = config.layout.offset_alignment(role);
+      if offset % alignment != 0 {
+        return Err(LaunchError::MisalignedOffset {
+          role,
+          offset,
+          alignment,
+        });
+      }
+
+      for (index, buffer) in buffers.iter().enumerate().skip(1) {
+        if buffer.inner().offset != 0 {
+          return Err(LaunchError::OffsetInBufferPart {
+            role,
+            index,
+            offset: buffer.inner().offset as u64,
+          });
+        }
+      }
+    }
+
+    Ok(())
   }
 
-  pub(crate) fn as_sys(&self) -> Pin<Box<LaunchParamsGuard>> {
+  pub(crate) fn as_sys(&self, config: &ConfigGuard) -> Pin<Box<LaunchParamsGuard>> {
     use std::mem::{transmute, zeroed};
 
     unsafe {
       let mut res = Box::pin(LaunchParamsGuard {
         params: zeroed(),
         command_buffer: self.command_buffer,
-        buffer: self.buffer.as_ref().map(Self::buffer_object),
-        temp_buffer: self.temp_buffer.as_ref().map(Self::buffer_object),
-        input_buffer: self.input_buffer.as_ref().map(Self::buffer_object),
-        output_buffer: self.output_buffer.as_ref().map(Self::buffer_object),
-        kernel: self.kernel.as_ref().map(Self::buffer_object),
+        buffer: Self::buffer_objects(&self.buffer),
+        temp_buffer: Self::buffer_objects(&self.temp_buffer),
+        input_buffer: Self::buffer_objects(&self.input_buffer),
+        output_buffer: Self::buffer_objects(&self.output_buffer),
+        kernel: Self::buffer_objects(&self.kernel),
       });
 
       res.params.commandBuffer = transmute(addr_of_mut!(res.command_buffer));
 
-      if let Some(b) = &res.buffer {
-        res.params.buffer = transmute(b);
+      // The configuration asks VkFFT to take all offsets from the launch parameters
+      res.params.bufferOffset = Self::buffer_offset(&self.buffer, config, BufferRole::Buffer);
+      res.params.tempBufferOffset =
+        Self::buffer_offset(&self.temp_buffer, config, BufferRole::TempBuffer);
+      res.params.inputBufferOffset =
+        Self::buffer_offset(&self.input_buffer, config, BufferRole::InputBuffer);
+      res.params.outputBufferOffset =
+        Self::buffer_offset(&self.output_buffer, config, BufferRole::OutputBuffer);
+      res.params.kernelOffset = Self::buffer_offset(&self.kernel, config, BufferRole::Kernel);
+
+      if !res.buffer.is_empty() {
+        res.params.buffer = transmute(res.buffer.as_mut_ptr());
       }
 
-      if let Some(b) = &res.temp_buffer {
-        res.params.tempBuffer = transmute(b);
+      if !res.temp_buffer.is_empty() {
+        res.params.tempBuffer = transmute(res.temp_buffer.as_mut_ptr());
       }
 
-      if let Some(b) = &res.input_buffer {
-        res.params.inputBuffer = transmute(b);
+      if !res.input_buffer.is_empty() {
+        res.params.inputBuffer = transmute(res.input_buffer.as_mut_ptr());
       }
 
-      if let Some(b) = &res.output_buffer {
-        res.params.outputBuffer = transmute(b);
+      if !res.output_buffer.is_empty() {
+        res.params.outputBuffer = transmute(res.output_buffer.as_mut_ptr());
       }
 
-      if let Some(k) = &res.kernel {
-        res.params.kernel = transmute(k);
+      if !res.kernel.is_empty() {
+        res.params.kernel = transmute(res.kernel.as_mut_ptr());
       }
 
       res
@@ -183,58 +376,327 @@ pub struct App {
 
   // Safety: We must keep a copy of the config to ensure our resources are kept alive
   config: Pin<Box<ConfigGuard>>,
+
+  device_uuid: [u8; 16],
+
+  // VkFFT doesn't keep the application string of a loaded plan, so hold on to it for `save_plan`
+  loaded_plan: Option<Vec<u8>>,
+
+  shader_sources: Option<ShaderSources>,
+
+  // Pass applying the normalizations VkFFT can't, recorded after each transform
+  scale: Option<ScalePass>,
 }
 
 impl App {
   pub fn new(config: Config) -> error::Result<Pin<Box<Self>>> {
+    Self::init(config, None)
+  }
+
+  /// Create an application from a plan saved with [`App::save_plan`], skipping shader compilation.
+  ///
+  /// The plan must have been saved by the same VkFFT version on the same device, and `config`
+  /// must describe the same transform it was saved with.
+  pub fn from_saved_plan(config: Config, data: &[u8]) -> error::Result<Pin<Box<Self>>> {
+    let plan = SavedPlan::from_bytes(data)?;
+    plan.check(crate::version(), config.physical_device.uuid())?;
+
+    Self::init(config, Some(plan.payload))
+  }
+
+  /// Create an application from a plan file written by [`App::save_plan_to_file`]
+  pub fn load_plan<P: AsRef<Path>>(config: Config, path: P) -> error::Result<Pin<Box<Self>>> {
+    let data = std::fs::read(path).map_err(PlanError::Io)?;
+    Self::from_saved_plan(config, &data)
+  }
+
+  fn init(config: Config, plan: Option<&[u8]>) -> error::Result<Pin<Box<Self>>> {
     use vkfft_sys::*;
 
+    #[cfg(feature = "tracing")]
+    let span = tracing::debug_span!(
+      "vkfft::App::new",
+      fft_dim = config.size.len(),
+      size = ?config.size,
+      precision = ?config.precision,
+      transform = %config.transform,
+      buffer_sizes = ?config.buffer_sizes(),
+      from_saved_plan = plan.is_some(),
+      init_duration = tracing::field::Empty,
+    )
+    .entered();
+
     let app: VkFFTApplication = unsafe { std::mem::zeroed() };
 
-    let sys_config = config.as_sys()?;
+    let device_uuid = *config.physical_device.uuid();
+    let mut sys_config = config.as_sys()?;
+
+    if let Some(plan) = plan {
+      // VkFFT refuses to save and load at once; the loaded plan is kept instead
+      sys_config.config.saveApplicationToString = 0;
+      sys_config.config.loadApplicationFromString = 1;
+      sys_config.config.loadApplicationString = plan.as_ptr() as *mut std::ffi::c_void;
+    }
 
     let mut res = Box::pin(Self {
       app,
       config: sys_config,
+      device_uuid,
+      loaded_plan: plan.map(<[u8]>::to_vec),
+      shader_sources: None,
+      scale: None,
     });
 
-    check_error(unsafe { initializeVkFFT(std::ptr::addr_of_mut!(res.app), res.config.config) })?;
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+
+    let operation = match plan {
+      Some(_) => Operation::LoadPlan,
+      None => Operation::Init,
+    };
+
+    check_error(unsafe { initializeVkFFT(std::ptr::addr_of_mut!(res.app), res.config.config) })
+      .map_err(|e| e.during(operation, res.config.summary()))?;
+
+    #[cfg(feature = "tracing")]
+    span.record("init_duration", tracing::field::debug(start.elapsed()));
+
+    if res.config.config.keepShaderCode != 0 {
+      res.shader_sources = Some(unsafe { ShaderSources::collect(&res.app) });
+    }
+
+    if res.config.scale.is_some() {
+      // The configured buffers the results of either direction are written to
+      let mut configured: Vec<vk::Buffer> = Vec::new();
+      for &direction in [Direction::Forward, Direction::Inverse].iter() {
+        let role = res.config.layout.result_role(direction);
+        for &buffer in res.config.buffer_guard(role).buffers.iter() {
+          if !configured.contains(&buffer) {
+            configured.push(buffer);
+          }
+        }
+      }
+
+      let device = res.config.keep_alive.device.clone();
+      let scale = ScalePass::new(device, res.config.layout.precision, &configured)
+        .map_err(|e| e.during(operation, res.config.summary()))?;
+      res.scale = Some(scale);
+    }
 
     Ok(res)
   }
 
+  /// GLSL generated for each axis, if the configuration was built with `keep_shader_code`. Plans
+  /// loaded with [`App::from_saved_plan`] have no sources.
+  pub fn shader_sources(&self) -> Option<&ShaderSources> {
+    self.shader_sources.as_ref()
+  }
+
+  /// Serialize the compiled plan, tagged with the VkFFT version and device UUID.
+  ///
+  /// Requires the configuration to be built with `save_plan`.
+  pub fn save_plan(&self) -> error::Result<Vec<u8>> {
+    let payload = match &self.loaded_plan {
+      Some(plan) => plan.as_slice(),
+      None if !self.app.saveApplicationString.is_null() => unsafe {
+        std::slice::from_raw_parts(
+          self.app.saveApplicationString as *const u8,
+          self.app.applicationStringSize as usize,
+        )
+      },
+      None => return Err(PlanError::NotSaved.into()),
+    };
+
+    Ok(
+      SavedPlan {
+        version: crate::version(),
+        device_uuid: self.device_uuid,
+        payload,
+      }
+      .to_bytes(),
+    )
+  }
+
+  pub fn save_plan_to_file<P: AsRef<Path>>(&self, path: P) -> error::Result<()> {
+    let data = self.save_plan()?;
+    std::fs::write(path, data).map_err(PlanError::Io)?;
+    Ok(())
+  }
+
+  /// Buffers the scaling pass runs over: those supplied for `role` at launch, or else the
+  /// configured ones
+  fn scale_parts(&self, params: &LaunchParams, role: BufferRole) -> Vec<Part> {
+    let supplied = params
+      .buffers()
+      .iter()
+      .find(|(r, _)| *r == role)
+      .map(|(_, buffers)| *buffers)
+      .unwrap_or(&[]);
+
+    if !supplied.is_empty() {
+      return supplied
+        .iter()
+        .map(|b| Part {
+          buffer: b.inner().buffer.internal_object().value(),
+          offset: b.inner().offset as u64,
+          size: b.size() as u64,
+          owner: Some(b.clone()),
+        })
+        .collect();
+    }
+
+    let guard = self.config.buffer_guard(role);
+    guard
+      .buffers
+      .iter()
+      .zip(guard.sizes.iter())
+      .enumerate()
+      .map(|(i, (&buffer, &size))| Part {
+        buffer,
+        // Only the first part starts at an offset
+        offset: if i == 0 { guard.offset } else { 0 },
+        size,
+        owner: None,
+      })
+      .collect()
+  }
+
   pub fn launch(&mut self, params: &mut LaunchParams, inverse: bool) -> error::Result<()> {
     use vkfft_sys::VkFFTAppend;
 
-    let mut params = params.as_sys();
+    let direction = if inverse {
+      Direction::Inverse
+    } else {
+      Direction::Forward
+    };
 
-    if self.config.buffer.is_some() && params.buffer.is_some() {
+    #[cfg(feature = "tracing")]
+    let _span = {
+      let layout = &self.config.layout;
+      tracing::debug_span!(
+        "vkfft::App::launch",
+        direction = %direction,
+        fft_dim = layout.size.len(),
+        size = ?layout.size,
+        precision = ?layout.precision,
+        transform = %layout.transform,
+        buffer_sizes = ?layout.buffer_sizes(),
+      )
+      .entered()
+    };
+
+    if !self.config.direction.supports(direction) {
+      return Err(
+        LaunchError::DirectionNotPlanned {
+          direction,
+          planned: self.config.direction,
+        }
+        .into(),
+      );
+    }
+
+    params.validate(&self.config)?;
+
+    if direction == Direction::Inverse
+      && self.config.layout.inverse_return_to_input_buffer
+      && self.config.input_buffer.buffers.is_empty()
+      && params.input_buffer.is_empty()
+    {
+      return Err(LaunchError::NoInputBuffer.into());
+    }
+
+    let layout = &self.config.layout;
+    let role = layout.result_role(direction);
+    let dispatches = match &self.scale {
+      Some(scale) => scale
+        .dispatches(
+          self.scale_parts(params, role),
+          layout.buffer_sizes().get(role),
+        )
+        .ok_or(LaunchError::NormalizationRangeTooLarge { role })?,
+      None => Vec::new(),
+    };
+
+    let command_buffer = params.command_buffer;
+    let mut params = params.as_sys(&self.config);
+
+    if !self.config.buffer.buffers.is_empty() && !params.buffer.is_empty() {
       return Err(LaunchError::ConfigSpecifiesBuffer.into());
     }
 
-    if self.config.temp_buffer.is_some() && params.temp_buffer.is_some() {
+    if !self.config.temp_buffer.buffers.is_empty() && !params.temp_buffer.is_empty() {
       return Err(LaunchError::ConfigSpecifiesTempBuffer.into());
     }
 
-    if self.config.input_buffer.is_some() && params.input_buffer.is_some() {
+    if !self.config.input_buffer.buffers.is_empty() && !params.input_buffer.is_empty() {
       return Err(LaunchError::ConfigSpecifiesInputBuffer.into());
     }
 
-    if self.config.output_buffer.is_some() && params.output_buffer.is_some() {
+    if !self.config.output_buffer.buffers.is_empty() && !params.output_buffer.is_empty() {
       return Err(LaunchError::ConfigSpecifiesOutputBuffer.into());
     }
 
+    if !self.config.kernel.buffers.is_empty() && !params.kernel.is_empty() {
+      return Err(LaunchError::ConfigSpecifiesKernel.into());
+    }
+
+    let operation = Operation::Append { direction };
+
     check_error(unsafe {
       VkFFTAppend(
         std::ptr::addr_of_mut!(self.app),
         if inverse { 1 } else { -1 },
         std::ptr::addr_of_mut!(params.params),
       )
-    })?;
+    })
+    .map_err(|e| e.during(operation, self.config.summary()))?;
+
+    let config = &self.config;
+    if let (Some(scale), Some(factor)) = (self.scale.as_mut(), config.scale) {
+      unsafe {
+        scale
+          .record(command_buffer, &dispatches, factor)
+          .map_err(|e| e.during(operation, config.summary()))?;
+      }
+    }
 
     Ok(())
   }
 
+  /// Locate the peak of a phase correlation surface this application computed, which is the shift
+  /// of the input relative to the kernel. This only searches the surface: record and submit the
+  /// forward transform first, then pass the contents of the buffer its result was written to.
+  /// `T` is the scalar type of that buffer.
+  pub fn phase_correlation_peak<T>(&self, surface: &[T]) -> error::Result<Shift>
+  where
+    T: Copy + Into<f64>,
+  {
+    if self.config.convolution_op != ConvolutionOp::PhaseCorrelation {
+      return Err(ShiftError::NotPhaseCorrelation.into());
+    }
+
+    let layout = &self.config.layout;
+    let role = layout.result_role(Direction::Forward);
+
+    let extent = layout.signal_extent();
+    let mut last = extent;
+    last.iter_mut().for_each(|len| *len -= 1);
+    let required = layout.scalar_index(role, last) as usize + 1;
+    if surface.len() < required {
+      return Err(
+        ShiftError::SurfaceTooSmall {
+          len: surface.len(),
+          required,
+        }
+        .into(),
+      );
+    }
+
+    Ok(find_shift(extent, |pos| {
+      surface[layout.scalar_index(role, pos) as usize].into()
+    }))
+  }
+
   pub fn forward(&mut self, params: &mut LaunchParams) -> error::Result<()> {
     self.launch(params, false)
   }
@@ -1,9 +1,10 @@
-use std::sync::Arc;
+use std::{any::TypeId, convert::TryFrom, fmt, marker::PhantomData, sync::Arc};
 
 use derive_more::{Display, Error};
+use half::f16;
 use std::pin::Pin;
 use vulkano::{
-  buffer::BufferAccess,
+  buffer::{BufferAccess, TypedBufferAccess},
   command_buffer::pool::UnsafeCommandPool,
   device::{Device, Queue},
   instance::PhysicalDevice,
@@ -13,6 +14,9 @@ use vulkano::{
 
 use std::ptr::addr_of_mut;
 
+/// Largest number of axes the linked VkFFT header supports
+pub const MAX_DIMENSIONS: usize = vkfft_sys::VKFFT_MAX_FFT_DIMENSIONS as usize;
+
 #[derive(Display, Debug, Error)]
 pub enum BuildError {
   NoPhysicalDevice,
@@ -21,11 +25,17 @@ pub enum BuildError {
   NoFence,
   NoCommandPool,
   NoBuffer,
+  Config(ConfigError),
+}
+
+impl From<ConfigError> for BuildError {
+  fn from(e: ConfigError) -> Self {
+    Self::Config(e)
+  }
 }
 
 pub struct ConfigBuilder<'a> {
-  fft_dim: u32,
-  size: [u32; 3usize],
+  size: Shape,
 
   physical_device: Option<PhysicalDevice<'a>>,
   device: Option<Arc<Device>>,
@@ -37,39 +47,56 @@ pub struct ConfigBuilder<'a> {
   output_buffer: Option<BufferDesc>,
   temp_buffer: Option<BufferDesc>,
   kernel: Option<BufferDesc>,
-  normalize: bool,
-  zero_padding: [bool; 3usize],
-  zeropad_left: [u32; 3usize],
-  zeropad_right: [u32; 3usize],
+  normalization: Normalization,
+  zero_padding: [bool; MAX_DIMENSIONS],
+  omit_dimension: [bool; MAX_DIMENSIONS],
+  zeropad_left: [u64; MAX_DIMENSIONS],
+  zeropad_right: [u64; MAX_DIMENSIONS],
+  frequency_zero_padding: bool,
+  pruned: Option<PrunedFft>,
   kernel_convolution: bool,
   convolution: bool,
-  r2c: bool,
+  transform: Transform,
   coordinate_features: u32,
   disable_reorder_four_step: bool,
   batch_count: Option<u32>,
   precision: Precision,
+  direction: Direction,
   use_lut: bool,
-  symmetric_kernel: bool,
+  tuning: Tuning,
+  convolution_mode: ConvolutionMode,
+  convolution_op: ConvolutionOp,
+  kernel_count: u32,
   input_formatted: Option<bool>,
   output_formatted: Option<bool>,
+  buffer_layout: Option<DataLayout>,
+  input_layout: Option<DataLayout>,
+  output_layout: Option<DataLayout>,
+  save_plan: bool,
+  keep_shader_code: bool,
+  print_memory_layout: bool,
+  inverse_return_to_input_buffer: bool,
+  error: Option<ConfigError>,
 }
 
 impl<'a> ConfigBuilder<'a> {
   pub fn new() -> Self {
     Self {
-      fft_dim: 1,
-      size: [1, 1, 1],
+      size: Shape::default(),
       physical_device: None,
       device: None,
       queue: None,
       fence: None,
       command_pool: None,
-      normalize: false,
-      zero_padding: [false, false, false],
-      zeropad_left: [0, 0, 0],
-      zeropad_right: [0, 0, 0],
+      normalization: Normalization::None,
+      zero_padding: [false; MAX_DIMENSIONS],
+      omit_dimension: [false; MAX_DIMENSIONS],
+      zeropad_left: [0; MAX_DIMENSIONS],
+      zeropad_right: [0; MAX_DIMENSIONS],
+      frequency_zero_padding: false,
+      pruned: None,
       kernel_convolution: false,
-      r2c: false,
+      transform: Transform::C2c,
       coordinate_features: 1,
       disable_reorder_four_step: false,
       buffer: None,
@@ -78,32 +105,63 @@ impl<'a> ConfigBuilder<'a> {
       output_buffer: None,
       batch_count: None,
       precision: Precision::Single,
+      direction: Direction::Both,
       convolution: false,
       use_lut: false,
-      symmetric_kernel: false,
+      tuning: Tuning::default(),
+      convolution_mode: ConvolutionMode::Vector,
+      convolution_op: ConvolutionOp::Convolution,
+      kernel_count: 1,
       input_formatted: None,
       output_formatted: None,
       kernel: None,
+      buffer_layout: None,
+      input_layout: None,
+      output_layout: None,
+      save_plan: false,
+      keep_shader_code: false,
+      print_memory_layout: false,
+      inverse_return_to_input_buffer: false,
+      error: None,
     }
   }
 
-  pub fn dim<const N: usize>(mut self, dim: &[u32; N]) -> Self {
-    let len = dim.len();
-    assert!(len <= 3);
+  /// Keep the first invalid setting so `build` can report it
+  fn fail(mut self, error: ConfigError) -> Self {
+    self.error.get_or_insert(error);
+    self
+  }
 
-    self.fft_dim = len as u32;
-    if len > 0 {
-      self.size[0] = dim[0];
-    }
-    if len > 1 {
-      self.size[1] = dim[1];
-    }
-    if len > 2 {
-      self.size[2] = dim[2];
+  /// Copy per-axis settings into `dst`, or record an error if there are more axes than VkFFT
+  /// supports
+  fn set_axes<T: Copy>(
+    mut self,
+    setting: &'static str,
+    values: &[T],
+    dst: fn(&mut Self) -> &mut [T; MAX_DIMENSIONS],
+  ) -> Self {
+    if values.len() > MAX_DIMENSIONS {
+      return self.fail(ConfigError::TooManyAxes {
+        setting,
+        count: values.len(),
+      });
     }
+
+    dst(&mut self)[..values.len()].copy_from_slice(values);
     self
   }
 
+  /// Size of each axis to transform. Invalid shapes are reported by `build`.
+  pub fn dim(mut self, dim: &[u64]) -> Self {
+    match Shape::new(dim) {
+      Ok(size) => {
+        self.size = size;
+        self
+      }
+      Err(e) => self.fail(e),
+    }
+  }
+
   pub fn physical_device(mut self, physical_device: PhysicalDevice<'a>) -> Self {
     self.physical_device = Some(physical_device);
     self
@@ -169,8 +227,13 @@ impl<'a> ConfigBuilder<'a> {
     self
   }
 
-  pub fn normalize(mut self) -> Self {
-    self.normalize = true;
+  /// Scale the inverse by `1 / N`, shorthand for `normalization(Normalization::Inverse)`
+  pub fn normalize(self) -> Self {
+    self.normalization(Normalization::Inverse)
+  }
+
+  pub fn normalization(mut self, normalization: Normalization) -> Self {
+    self.normalization = normalization;
     self
   }
 
@@ -179,8 +242,39 @@ impl<'a> ConfigBuilder<'a> {
     self
   }
 
+  /// Use a symmetric matrix kernel. Shorthand for `convolution_mode(ConvolutionMode::SymmetricMatrix)`.
   pub fn symmetric_kernel(mut self) -> Self {
-    self.symmetric_kernel = true;
+    self.convolution_mode = ConvolutionMode::SymmetricMatrix;
+    self
+  }
+
+  pub fn convolution_mode(mut self, convolution_mode: ConvolutionMode) -> Self {
+    self.convolution_mode = convolution_mode;
+    self
+  }
+
+  pub fn convolution_op(mut self, convolution_op: ConvolutionOp) -> Self {
+    self.convolution_op = convolution_op;
+    self
+  }
+
+  /// Cross-correlate the input with the kernel instead of convolving them. Implies `convolution`.
+  pub fn cross_correlation(mut self) -> Self {
+    self.convolution = true;
+    self.convolution_op = ConvolutionOp::CrossCorrelation;
+    self
+  }
+
+  /// Compute the phase correlation of the input with the kernel. Implies `convolution`.
+  pub fn phase_correlation(mut self) -> Self {
+    self.convolution = true;
+    self.convolution_op = ConvolutionOp::PhaseCorrelation;
+    self
+  }
+
+  /// Number of kernels a convolution applies in one pass, or a kernel convolution transforms
+  pub fn kernel_count(mut self, kernel_count: u32) -> Self {
+    self.kernel_count = kernel_count;
     self
   }
 
@@ -189,8 +283,14 @@ impl<'a> ConfigBuilder<'a> {
     self
   }
 
+  /// Perform an R2C forward and C2R inverse transform. Shorthand for `transform(Transform::R2c)`.
   pub fn r2c(mut self) -> Self {
-    self.r2c = true;
+    self.transform = Transform::R2c;
+    self
+  }
+
+  pub fn transform(mut self, transform: Transform) -> Self {
+    self.transform = transform;
     self
   }
 
@@ -199,61 +299,67 @@ impl<'a> ConfigBuilder<'a> {
     self
   }
 
+  /// Keep the compiled plan so it can be retrieved with [`App::save_plan`](crate::app::App::save_plan)
+  pub fn save_plan(mut self) -> Self {
+    self.save_plan = true;
+    self
+  }
+
+  /// Keep the generated GLSL, available from [`App::shader_sources`](crate::app::App::shader_sources)
+  pub fn keep_shader_code(mut self) -> Self {
+    self.keep_shader_code = true;
+    self
+  }
+
+  /// Have VkFFT print the memory layout of each dispatch to stdout while planning
+  pub fn print_memory_layout(mut self) -> Self {
+    self.print_memory_layout = true;
+    self
+  }
+
   pub fn coordinate_features(mut self, coordinate_features: u32) -> Self {
     self.coordinate_features = coordinate_features;
     self
   }
 
+  pub fn tuning(mut self, tuning: Tuning) -> Self {
+    self.tuning = tuning;
+    self
+  }
+
   pub fn disable_reorder_four_step(mut self) -> Self {
     self.disable_reorder_four_step = true;
     self
   }
 
-  pub fn zero_padding<const N: usize>(mut self, zero_padding: &[bool; N]) -> Self {
-    let len = zero_padding.len();
-    assert!(len <= 3);
+  pub fn zero_padding(self, zero_padding: &[bool]) -> Self {
+    self.set_axes("zero_padding", zero_padding, |b| &mut b.zero_padding)
+  }
 
-    if len > 0 {
-      self.zero_padding[0] = zero_padding[0];
-    }
-    if len > 1 {
-      self.zero_padding[1] = zero_padding[1];
-    }
-    if len > 2 {
-      self.zero_padding[2] = zero_padding[2];
-    }
-    self
+  /// Skip the transform along the given axes while keeping them as layout dimensions
+  pub fn omit_dimension(self, omit_dimension: &[bool]) -> Self {
+    self.set_axes("omit_dimension", omit_dimension, |b| &mut b.omit_dimension)
   }
 
-  pub fn zeropad_left<const N: usize>(mut self, zeropad_left: &[u32; N]) -> Self {
-    let len = zeropad_left.len();
-    assert!(len <= 3);
+  pub fn zeropad_left(self, zeropad_left: &[u64]) -> Self {
+    self.set_axes("zeropad_left", zeropad_left, |b| &mut b.zeropad_left)
+  }
 
-    if len > 0 {
-      self.zeropad_left[0] = zeropad_left[0];
-    }
-    if len > 1 {
-      self.zeropad_left[1] = zeropad_left[1];
-    }
-    if len > 2 {
-      self.zeropad_left[2] = zeropad_left[2];
-    }
-    self
+  pub fn zeropad_right(self, zeropad_right: &[u64]) -> Self {
+    self.set_axes("zeropad_right", zeropad_right, |b| &mut b.zeropad_right)
   }
 
-  pub fn zeropad_right<const N: usize>(mut self, zeropad_right: &[u32; N]) -> Self {
-    let len = zeropad_right.len();
-    assert!(len <= 3);
+  /// Place the `zeropad_left..zeropad_right` blocks in the frequency domain: the forward transform
+  /// doesn't compute them and the inverse reads them as zeros
+  pub fn frequency_zero_padding(mut self) -> Self {
+    self.frequency_zero_padding = true;
+    self
+  }
 
-    if len > 0 {
-      self.zeropad_right[0] = zeropad_right[0];
-    }
-    if len > 1 {
-      self.zeropad_right[1] = zeropad_right[1];
-    }
-    if len > 2 {
-      self.zeropad_right[2] = zeropad_right[2];
-    }
+  /// Derive `zero_padding`, the zero blocks and their domain from a pruned transform, replacing
+  /// any set directly
+  pub fn pruned(mut self, pruned: PrunedFft) -> Self {
+    self.pruned = Some(pruned);
     self
   }
 
@@ -272,7 +378,112 @@ impl<'a> ConfigBuilder<'a> {
     self
   }
 
+  /// Use a custom layout for `buffer`, and for the input and output buffers unless they are
+  /// formatted
+  pub fn buffer_layout(mut self, layout: DataLayout) -> Self {
+    self.buffer_layout = Some(layout);
+    self
+  }
+
+  /// Use a custom layout for the input buffer. This implies `input_formatted(true)`.
+  pub fn input_layout(mut self, layout: DataLayout) -> Self {
+    self.input_formatted = Some(true);
+    self.input_layout = Some(layout);
+    self
+  }
+
+  /// Use a custom layout for the output buffer. This implies `output_formatted(true)`.
+  pub fn output_layout(mut self, layout: DataLayout) -> Self {
+    self.output_formatted = Some(true);
+    self.output_layout = Some(layout);
+    self
+  }
+
+  /// Have the inverse write its result to the input buffer instead of `buffer`, e.g. to turn an
+  /// out-of-place C2R back into the real data it came from. This implies `input_formatted(true)`.
+  pub fn inverse_return_to_input_buffer(mut self) -> Self {
+    self.input_formatted = Some(true);
+    self.inverse_return_to_input_buffer = true;
+    self
+  }
+
+  pub fn precision(mut self, precision: Precision) -> Self {
+    self.precision = precision;
+    self
+  }
+
+  /// Only compile the given direction of the transform
+  pub fn direction(mut self, direction: Direction) -> Self {
+    self.direction = direction;
+    self
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      size: self.size,
+      transform: self.transform,
+      precision: self.precision,
+      coordinate_features: self.coordinate_features,
+      batch_count: self.batch_count.unwrap_or(1),
+      kernel_convolution: self.kernel_convolution,
+      convolution: self.convolution,
+      convolution_mode: self.convolution_mode,
+      kernel_count: self.kernel_count,
+      input_formatted: self.input_formatted.unwrap_or(false),
+      output_formatted: self.output_formatted.unwrap_or(false),
+      buffer_layout: self.buffer_layout,
+      input_layout: self.input_layout,
+      output_layout: self.output_layout,
+      inverse_return_to_input_buffer: self.inverse_return_to_input_buffer,
+    }
+  }
+
+  /// The settings besides the layout, with the zero blocks of a pruned transform resolved
+  fn options(&self) -> Result<Options, ConfigError> {
+    let mut options = Options {
+      normalization: self.normalization,
+      zero_padding: self.zero_padding,
+      omit_dimension: self.omit_dimension,
+      zeropad_left: self.zeropad_left,
+      zeropad_right: self.zeropad_right,
+      frequency_zero_padding: self.frequency_zero_padding,
+      convolution_op: self.convolution_op,
+      disable_reorder_four_step: self.disable_reorder_four_step,
+      tuning: self.tuning,
+      direction: self.direction,
+      save_plan: self.save_plan,
+      keep_shader_code: self.keep_shader_code,
+      print_memory_layout: self.print_memory_layout,
+    };
+
+    if let Some(pruned) = &self.pruned {
+      options.zero_padding = [false; MAX_DIMENSIONS];
+      options.frequency_zero_padding = pruned.frequency;
+
+      for (axis, block) in pruned.zero_blocks(&self.size)?.iter().enumerate() {
+        if let Some((left, right)) = *block {
+          options.zero_padding[axis] = true;
+          options.zeropad_left[axis] = left;
+          options.zeropad_right[axis] = right;
+        }
+      }
+    }
+
+    Ok(options)
+  }
+
+  /// Compute the buffer sizes required by the layout configured so far
+  pub fn buffer_sizes(&self) -> BufferSizes {
+    self.layout().buffer_sizes()
+  }
+
   pub fn build(self) -> Result<Config<'a>, BuildError> {
+    if let Some(e) = self.error {
+      return Err(e.into());
+    }
+
+    let options = self.options()?;
+
     let physical_device = match self.physical_device {
       Some(v) => v,
       None => return Err(BuildError::NoPhysicalDevice),
@@ -298,161 +509,1752 @@ impl<'a> ConfigBuilder<'a> {
       None => return Err(BuildError::NoCommandPool),
     };
 
-    Ok(Config {
-      fft_dim: self.fft_dim,
+    let config = Config {
       size: self.size,
       physical_device,
       device,
       queue,
       fence,
       command_pool,
-      normalize: self.normalize,
-      zero_padding: self.zero_padding,
-      zeropad_left: self.zeropad_left,
-      zeropad_right: self.zeropad_right,
+      normalization: self.normalization,
+      zero_padding: options.zero_padding,
+      omit_dimension: self.omit_dimension,
+      zeropad_left: options.zeropad_left,
+      zeropad_right: options.zeropad_right,
+      frequency_zero_padding: options.frequency_zero_padding,
       kernel_convolution: self.kernel_convolution,
-      r2c: self.r2c,
+      transform: self.transform,
       coordinate_features: self.coordinate_features,
       disable_reorder_four_step: self.disable_reorder_four_step,
       buffer: self.buffer,
       batch_count: self.batch_count,
       precision: self.precision,
+      direction: self.direction,
       convolution: self.convolution,
       use_lut: self.use_lut,
-      symmetric_kernel: self.symmetric_kernel,
+      tuning: self.tuning,
+      convolution_mode: self.convolution_mode,
+      convolution_op: self.convolution_op,
+      kernel_count: self.kernel_count,
       input_formatted: self.input_formatted,
       output_formatted: self.output_formatted,
       kernel: self.kernel,
       temp_buffer: self.temp_buffer,
       input_buffer: self.input_buffer,
       output_buffer: self.output_buffer,
-    })
-  }
-}
+      buffer_layout: self.buffer_layout,
+      input_layout: self.input_layout,
+      output_layout: self.output_layout,
+      save_plan: self.save_plan,
+      keep_shader_code: self.keep_shader_code,
+      print_memory_layout: self.print_memory_layout,
+      inverse_return_to_input_buffer: self.inverse_return_to_input_buffer,
+    };
 
-pub enum Precision {
-  /// Perform calculations in single precision (32-bit)
-  Single,
-  /// Perform calculations in double precision (64-bit)
-  Double,
-  /// Perform calculations in half precision (16-bit)
-  Half,
-  /// Use half precision only as input/output buffer. Input/Output have to be allocated as half,
-  /// buffer/tempBuffer have to be allocated as float (out of place mode only).
-  HalfMemory,
+    config.validate()?;
+
+    Ok(config)
+  }
 }
 
-pub enum BufferDesc {
-  Buffer(Arc<dyn BufferAccess>),
-  BufferSize(usize),
+/// A [`ConfigBuilder`] whose buffers must hold `T`, so a buffer of the wrong element type is
+/// rejected at compile time. The precision is derived from `T`.
+pub struct TypedConfigBuilder<'a, T> {
+  builder: ConfigBuilder<'a>,
+  element: PhantomData<T>,
 }
 
-impl<T> From<Arc<T>> for BufferDesc
+impl<'a, T> TypedConfigBuilder<'a, T>
 where
-  T: 'static + BufferAccess,
+  T: Scalar,
 {
-  fn from(value: Arc<T>) -> Self {
-    Self::Buffer(value as Arc<dyn BufferAccess>)
+  pub fn new() -> Self {
+    Self {
+      builder: ConfigBuilder::new().precision(T::PRECISION),
+      element: PhantomData,
+    }
+  }
+
+  /// Apply untyped settings, such as the dimensions or devices, to the underlying builder.
+  /// Changing the precision or setting a buffer whose element type isn't known fails the build.
+  pub fn configure<F>(mut self, f: F) -> Self
+  where
+    F: FnOnce(ConfigBuilder<'a>) -> ConfigBuilder<'a>,
+  {
+    let builder = f(self.builder);
+
+    if builder.precision != T::PRECISION {
+      let found = builder.precision;
+      self.builder = builder.fail(ConfigError::TypedPrecisionChanged {
+        expected: T::PRECISION,
+        found,
+      });
+      return self;
+    }
+
+    let roles = [
+      (BufferRole::Buffer, &builder.buffer),
+      (BufferRole::InputBuffer, &builder.input_buffer),
+      (BufferRole::OutputBuffer, &builder.output_buffer),
+      (BufferRole::TempBuffer, &builder.temp_buffer),
+      (BufferRole::Kernel, &builder.kernel),
+    ];
+    let untyped = roles.iter().find_map(|(role, desc)| {
+      desc
+        .iter()
+        .flat_map(|desc| desc.parts())
+        .any(|part| matches!(part, BufferDesc::Buffer(_)))
+        .then(|| *role)
+    });
+
+    self.builder = match untyped {
+      Some(role) => builder.fail(ConfigError::UntypedBuffer { role }),
+      None => builder,
+    };
+    self
+  }
+
+  pub fn buffer<B>(mut self, buffer: Arc<B>) -> Self
+  where
+    B: 'static + TypedBufferAccess<Content = [T]>,
+  {
+    self.builder = self.builder.buffer(buffer);
+    self
+  }
+
+  pub fn temp_buffer<B>(mut self, temp_buffer: Arc<B>) -> Self
+  where
+    B: 'static + TypedBufferAccess<Content = [T]>,
+  {
+    self.builder = self.builder.temp_buffer(temp_buffer);
+    self
+  }
+
+  pub fn input_buffer<B>(mut self, input_buffer: Arc<B>) -> Self
+  where
+    B: 'static + TypedBufferAccess<Content = [T]>,
+  {
+    self.builder = self.builder.input_buffer(input_buffer);
+    self
+  }
+
+  pub fn output_buffer<B>(mut self, output_buffer: Arc<B>) -> Self
+  where
+    B: 'static + TypedBufferAccess<Content = [T]>,
+  {
+    self.builder = self.builder.output_buffer(output_buffer);
+    self
+  }
+
+  pub fn kernel<B>(mut self, kernel: Arc<B>) -> Self
+  where
+    B: 'static + TypedBufferAccess<Content = [T]>,
+  {
+    self.builder = self.builder.kernel(kernel);
+    self
+  }
+
+  pub fn buffer_sizes(&self) -> BufferSizes {
+    self.builder.buffer_sizes()
+  }
+
+  pub fn build(self) -> Result<Config<'a>, BuildError> {
+    self.builder.build()
   }
 }
 
-impl From<usize> for BufferDesc {
-  fn from(value: usize) -> Self {
-    Self::BufferSize(value)
+/// How the results of the transforms are scaled, where `N` is the number of values of the
+/// transformed axes. Scaling other than `None` and `Inverse` runs as an extra pass after VkFFT's
+/// dispatches, over the whole result buffer including any padding. That pass holds on to the
+/// buffers supplied at the last launch until one supplies others, which, as for VkFFT's own
+/// dispatches, requires the command buffers recorded with the previous ones to have finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Normalization {
+  /// Leave both directions unscaled, so a round trip multiplies the data by `N`
+  None,
+  /// Scale the inverse by `1 / N`, which VkFFT does while transforming
+  Inverse,
+  /// Scale both directions by `1 / sqrt(N)`, making the transform unitary. Only supported for
+  /// C2C and R2C transforms outside of convolutions.
+  Orthonormal,
+  /// Multiply the results of both directions by a factor, without any other normalization
+  Custom(f64),
+}
+
+impl Default for Normalization {
+  fn default() -> Self {
+    Self::None
   }
 }
 
-impl BufferDesc {
-  pub fn size(&self) -> usize {
+impl Normalization {
+  /// Factor the extra pass multiplies results by, for transforms over `len` values
+  pub(crate) fn scale(self, len: u64) -> Option<f64> {
     match self {
-      Self::Buffer(b) => b.size(),
-      Self::BufferSize(b) => *b,
+      Self::None | Self::Inverse => None,
+      Self::Orthonormal => Some(1.0 / (len as f64).sqrt()),
+      Self::Custom(factor) => Some(factor),
     }
   }
 
-  pub fn as_buffer(&self) -> Option<&Arc<dyn BufferAccess>> {
-    match self {
-      Self::Buffer(b) => Some(b),
-      Self::BufferSize(_) => None,
+  /// Check VkFFT or the extra pass can scale the results of an application with `layout`
+  pub(crate) fn validate(self, layout: &Layout) -> Result<(), ConfigError> {
+    let convolution = layout.convolution || layout.kernel_convolution;
+    if self == Self::Orthonormal && (layout.transform.is_dct() || convolution) {
+      return Err(ConfigError::UnsupportedNormalization {
+        normalization: self,
+      });
     }
-  }
 
-  pub fn as_buffer_size(&self) -> Option<&usize> {
-    match self {
-      Self::Buffer(_) => None,
-      Self::BufferSize(b) => Some(b),
+    // Whether the extra pass runs doesn't depend on the number of values
+    if let (Some(_), Precision::Half | Precision::HalfMemory) = (self.scale(1), layout.precision) {
+      return Err(ConfigError::UnsupportedNormalizationPrecision {
+        normalization: self,
+        precision: layout.precision,
+      });
     }
+
+    Ok(())
   }
 }
 
-pub struct Config<'a> {
-  pub fft_dim: u32,
-  pub size: [u32; 3usize],
+/// Transform that only reads or produces part of each axis, passed to
+/// [`ConfigBuilder::pruned`].
+///
+/// Each axis keeps `len` values starting at `start`, wrapping around the end of the axis, and VkFFT
+/// skips the rest. The skipped values must form a single block that doesn't wrap, so the kept
+/// region has to touch the start or the end of the axis, or wrap around it. Axes not mentioned
+/// are kept whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunedFft {
+  frequency: bool,
+  kept: Vec<(usize, u64, u64)>,
+}
 
-  pub physical_device: PhysicalDevice<'a>,
-  pub device: Arc<Device>,
-  pub queue: Arc<Queue>,
-  pub fence: &'a Fence,
-  pub command_pool: Arc<UnsafeCommandPool>,
+impl PrunedFft {
+  /// Only the kept region of the input of the forward transform may be non-zero. The rest is read
+  /// as zeros, whatever the buffer holds there.
+  pub fn nonzero_input() -> Self {
+    Self {
+      frequency: false,
+      kept: Vec::new(),
+    }
+  }
 
-  pub buffer: Option<BufferDesc>,
-  pub input_buffer: Option<BufferDesc>,
-  pub output_buffer: Option<BufferDesc>,
-  pub temp_buffer: Option<BufferDesc>,
-  pub kernel: Option<BufferDesc>,
+  /// Only the kept region of the spectrum is needed. The forward transform leaves the rest of its
+  /// output unwritten and the inverse reads it as zeros.
+  pub fn needed_output() -> Self {
+    Self {
+      frequency: true,
+      kept: Vec::new(),
+    }
+  }
 
-  /// Normalize inverse transform
-  pub normalize: bool,
+  /// Keep `len` values of `axis` starting at `start`
+  pub fn keep(mut self, axis: usize, start: u64, len: u64) -> Self {
+    self.kept.retain(|&(a, _, _)| a != axis);
+    self.kept.push((axis, start, len));
+    self
+  }
 
-  /// Don't read some data/perform computations if some input sequences are zeropadded for each axis
-  pub zero_padding: [bool; 3usize],
+  /// Whether the zero blocks are in the frequency domain
+  pub fn is_frequency_domain(&self) -> bool {
+    self.frequency
+  }
 
-  /// Specify start boundary of zero block in the system for each axis
-  pub zeropad_left: [u32; 3usize],
+  /// `zeropad_left` and `zeropad_right` of each axis with a block to skip
+  pub(crate) fn zero_blocks(
+    &self,
+    size: &Shape,
+  ) -> Result<[Option<(u64, u64)>; MAX_DIMENSIONS], ConfigError> {
+    let mut res = [None; MAX_DIMENSIONS];
+
+    for &(axis, start, len) in &self.kept {
+      if axis >= size.len() {
+        return Err(ConfigError::PrunedAxisOutOfRange {
+          axis,
+          fft_dim: size.len(),
+        });
+      }
 
-  /// Specify end boundary of zero block in the system for each axis
-  pub zeropad_right: [u32; 3usize],
+      let n = size[axis];
+      let invalid = ConfigError::InvalidPruning {
+        axis,
+        start,
+        len,
+        size: n,
+      };
 
-  /// Specify if this application is used to create kernel for convolution, so it has the same properties
-  pub kernel_convolution: bool,
+      if len == 0 || len > n || start >= n {
+        return Err(invalid);
+      }
 
-  /// Perform convolution in this application (0 - off, 1 - on). Disables reorderFourStep parameter
-  pub convolution: bool,
+      // The skipped values follow the kept ones around the axis
+      let left = (start + len) % n;
+      let right = left + (n - len);
+      if right > n {
+        return Err(invalid);
+      }
 
-  /// Perform R2C/C2R decomposition
-  pub r2c: bool,
+      if left != right {
+        res[axis] = Some((left, right));
+      }
+    }
 
-  /// C - coordinate, or dimension of features vector. In matrix convolution - size of vector
-  pub coordinate_features: u32,
+    Ok(res)
+  }
+}
 
-  /// Disables unshuffling of four step algorithm. Requires `temp_buffer` allocation.
-  pub disable_reorder_four_step: bool,
+/// Kind of transform an application computes.
+///
+/// C2C buffers hold interleaved complex values. R2C reads real values and stores the
+/// `size[0] / 2 + 1` non-redundant complex values along the first axis; when a real buffer isn't
+/// formatted it shares the complex layout, so each row is padded to `2 * (size[0] / 2 + 1)` reals.
+/// The DCTs map real values to real values: every buffer holds `size[0] * size[1] * size[2]`
+/// unpadded reals per coordinate feature and batch, and the result has the layout of the input.
+/// The inverse of DCT-II is DCT-III and vice versa, DCT-I and DCT-IV are their own inverses.
+/// Like FFTW, VkFFT doesn't normalize DCTs.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+  /// Complex to complex
+  #[display(fmt = "C2C")]
+  C2c,
+  /// Real to complex forward, complex to real inverse
+  #[display(fmt = "R2C")]
+  R2c,
+  /// Real to real DCT-I, `y_k = x_0 + (-1)^k x_{n-1} + 2 sum_{j=1}^{n-2} x_j cos(pi j k / (n - 1))`
+  #[display(fmt = "DCT-I")]
+  Dct1,
+  /// Real to real DCT-II, `y_k = 2 sum_j x_j cos(pi (j + 1/2) k / n)`
+  #[display(fmt = "DCT-II")]
+  Dct2,
+  /// Real to real DCT-III, `y_k = x_0 + 2 sum_{j=1}^{n-1} x_j cos(pi j (k + 1/2) / n)`
+  #[display(fmt = "DCT-III")]
+  Dct3,
+  /// Real to real DCT-IV, `y_k = 2 sum_j x_j cos(pi (j + 1/2) (k + 1/2) / n)`
+  #[display(fmt = "DCT-IV")]
+  Dct4,
+}
 
-  /// Used to perform multiple batches of initial data
-  pub batch_count: Option<u32>,
+impl Transform {
+  pub fn is_dct(&self) -> bool {
+    self.dct_type() != 0
+  }
 
-  pub precision: Precision,
+  /// The value of VkFFT's `performDCT`
+  fn dct_type(&self) -> u64 {
+    match self {
+      Self::C2c | Self::R2c => 0,
+      Self::Dct1 => 1,
+      Self::Dct2 => 2,
+      Self::Dct3 => 3,
+      Self::Dct4 => 4,
+    }
+  }
+}
 
-  /// Switches from calculating sincos to using precomputed LUT tables
-  pub use_lut: bool,
+/// How the kernel of a convolution combines the `C` coordinate features of the input.
+///
+/// Kernels are stored like the frequency domain data of the application transforming them, with
+/// the number of coordinate features given below. The elements of a matrix kernel are stored row
+/// by row, those of a symmetric one as the upper triangle (`xx, xy, yy` or
+/// `xx, xy, xz, yy, yz, zz`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvolutionMode {
+  /// Single feature data convolved with a single feature kernel. Requires `C = 1`.
+  Scalar,
+  /// Each feature convolved with its own kernel feature, `C` kernel features
+  Vector,
+  /// The feature vector multiplied by a `C x C` matrix kernel, `C * C` kernel features.
+  /// Requires `C` to be 2 or 3.
+  Matrix,
+  /// Like `Matrix` with a symmetric kernel, `C * (C + 1) / 2` kernel features
+  SymmetricMatrix,
+}
 
-  /// Specify if kernel in 2x2 or 3x3 matrix convolution is symmetric
-  pub symmetric_kernel: bool,
+impl ConvolutionMode {
+  /// Whether the mode can be used with the given number of coordinate features
+  pub fn supports(&self, coordinate_features: u32) -> bool {
+    match self {
+      Self::Scalar => coordinate_features == 1,
+      Self::Vector => coordinate_features >= 1,
+      Self::Matrix | Self::SymmetricMatrix => (2..=3).contains(&coordinate_features),
+    }
+  }
 
-  /// specify if input buffer is padded - false is padded, true is not padded.
-  /// For example if it is not padded for R2C if out-of-place mode is selected
-  /// (only if numberBatches==1 and numberKernels==1)
-  pub input_formatted: Option<bool>,
+  /// Number of coordinate features of a kernel
+  pub fn kernel_features(&self, coordinate_features: u32) -> u32 {
+    match self {
+      Self::Scalar | Self::Vector => coordinate_features,
+      Self::Matrix => coordinate_features * coordinate_features,
+      Self::SymmetricMatrix => coordinate_features * (coordinate_features + 1) / 2,
+    }
+  }
 
-  /// specify if output buffer is padded - false is padded, true is not padded.
-  /// For example if it is not padded for R2C if out-of-place mode is selected
-  /// (only if numberBatches==1 and numberKernels==1)
-  pub output_formatted: Option<bool>,
+  /// The value of VkFFT's `matrixConvolution`
+  fn matrix_size(&self, coordinate_features: u32) -> u32 {
+    match self {
+      Self::Scalar | Self::Vector => 1,
+      Self::Matrix | Self::SymmetricMatrix => coordinate_features,
+    }
+  }
 }
 
-#[derive(Display, Debug, Error)]
-pub enum ConfigError {
-  InvalidConfig,
+/// What a convolution computes from the spectra of the input and the kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvolutionOp {
+  /// Multiply the spectra, convolving the input with the kernel
+  Convolution,
+  /// Multiply the input spectrum by the conjugate of the kernel spectrum. The output at shift `s`
+  /// is `sum_n x[n + s] * conj(k[n])`, so it peaks where the input matches the kernel shifted by
+  /// `s`.
+  CrossCorrelation,
+  /// Like `CrossCorrelation`, normalizing the product of the spectra to unit magnitude. The
+  /// output is close to a delta at the shift between the input and the kernel, which
+  /// [`App::phase_correlation_peak`](crate::app::App::phase_correlation_peak) locates.
+  PhaseCorrelation,
+}
+
+impl ConvolutionOp {
+  fn apply(&self, config: &mut vkfft_sys::VkFFTConfiguration) {
+    match self {
+      Self::Convolution => {}
+      // 2 conjugates the kernel, 1 would conjugate the input sequence instead
+      Self::CrossCorrelation => config.conjugateConvolution = 2,
+      Self::PhaseCorrelation => {
+        config.conjugateConvolution = 2;
+        config.crossPowerSpectrumNormalization = true.into();
+      }
+    }
+  }
+}
+
+/// Directions of the transform an application supports
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+  /// Compile both the forward and the inverse transform
+  #[display(fmt = "forward and inverse")]
+  Both,
+  /// Only compile the forward transform
+  #[display(fmt = "forward")]
+  Forward,
+  /// Only compile the inverse transform
+  #[display(fmt = "inverse")]
+  Inverse,
+}
+
+impl Direction {
+  /// Whether an application compiled for `self` can run a transform in `direction`
+  pub fn supports(&self, direction: Direction) -> bool {
+    *self == Self::Both || *self == direction
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+  /// Perform calculations in single precision (32-bit)
+  Single,
+  /// Perform calculations in double precision (64-bit)
+  Double,
+  /// Perform calculations in half precision (16-bit)
+  Half,
+  /// Use half precision only as input/output buffer. Input/Output have to be allocated as half,
+  /// buffer/tempBuffer have to be allocated as float (out of place mode only).
+  HalfMemory,
+}
+
+impl Precision {
+  /// Size in bytes of a single real value in `buffer` and `temp_buffer`
+  fn scalar_size(&self) -> u64 {
+    match self {
+      Self::Single => 4,
+      Self::Double => 8,
+      Self::Half => 2,
+      Self::HalfMemory => 4,
+    }
+  }
+
+  /// Element type buffers of the given role must be allocated with
+  pub fn element_type(&self, role: BufferRole) -> ElementType {
+    match (self, role) {
+      (Self::Single, _) => ElementType::F32,
+      (Self::Double, _) => ElementType::F64,
+      (Self::Half, _) => ElementType::F16,
+      (Self::HalfMemory, BufferRole::InputBuffer | BufferRole::OutputBuffer) => ElementType::F16,
+      (Self::HalfMemory, _) => ElementType::F32,
+    }
+  }
+
+  /// Size in bytes of a single real value in `input_buffer` and `output_buffer`
+  fn io_scalar_size(&self) -> u64 {
+    match self {
+      Self::HalfMemory => 2,
+      _ => self.scalar_size(),
+    }
+  }
+}
+
+/// Element type of a buffer's contents, where it is known
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementType {
+  #[display(fmt = "f16")]
+  F16,
+  #[display(fmt = "f32")]
+  F32,
+  #[display(fmt = "f64")]
+  F64,
+}
+
+impl ElementType {
+  /// Recognize slices of scalars or of interleaved complex pairs
+  pub fn of<T>() -> Option<Self>
+  where
+    T: 'static + ?Sized,
+  {
+    let id = TypeId::of::<T>();
+
+    if id == TypeId::of::<[f32]>() || id == TypeId::of::<[[f32; 2]]>() {
+      Some(Self::F32)
+    } else if id == TypeId::of::<[f64]>() || id == TypeId::of::<[[f64; 2]]>() {
+      Some(Self::F64)
+    } else if id == TypeId::of::<[f16]>() || id == TypeId::of::<[[f16; 2]]>() {
+      Some(Self::F16)
+    } else {
+      None
+    }
+  }
+}
+
+/// Real scalar types VkFFT can compute with
+pub trait Scalar: 'static + Copy {
+  const PRECISION: Precision;
+}
+
+impl Scalar for f32 {
+  const PRECISION: Precision = Precision::Single;
+}
+
+impl Scalar for f64 {
+  const PRECISION: Precision = Precision::Double;
+}
+
+impl Scalar for f16 {
+  const PRECISION: Precision = Precision::Half;
+}
+
+pub enum BufferDesc {
+  Buffer(Arc<dyn BufferAccess>),
+  /// A buffer whose element type is known, so it can be checked against the precision
+  TypedBuffer(Arc<dyn BufferAccess>, ElementType),
+  BufferSize(u64),
+  /// A role split across several `VkBuffer`s, e.g. to stay within `maxStorageBufferRange`. The
+  /// parts must either all be buffers or all be sizes, and only the first may have an offset.
+  Buffers(Vec<BufferDesc>),
+}
+
+impl<T> From<Arc<T>> for BufferDesc
+where
+  T: 'static + TypedBufferAccess,
+  T::Content: 'static,
+{
+  fn from(value: Arc<T>) -> Self {
+    match ElementType::of::<T::Content>() {
+      Some(element_type) => Self::TypedBuffer(value as Arc<dyn BufferAccess>, element_type),
+      None => Self::Buffer(value as Arc<dyn BufferAccess>),
+    }
+  }
+}
+
+impl fmt::Debug for BufferDesc {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Buffer(buffer) => f
+        .debug_struct("Buffer")
+        .field("size", &buffer.size())
+        .field("offset", &buffer.inner().offset)
+        .finish(),
+      Self::TypedBuffer(buffer, element_type) => f
+        .debug_struct("TypedBuffer")
+        .field("size", &buffer.size())
+        .field("offset", &buffer.inner().offset)
+        .field("element_type", element_type)
+        .finish(),
+      Self::BufferSize(size) => f.debug_tuple("BufferSize").field(size).finish(),
+      Self::Buffers(parts) => f.debug_tuple("Buffers").field(parts).finish(),
+    }
+  }
+}
+
+impl From<u64> for BufferDesc {
+  fn from(value: u64) -> Self {
+    Self::BufferSize(value)
+  }
+}
+
+impl<B> From<Vec<B>> for BufferDesc
+where
+  B: Into<BufferDesc>,
+{
+  fn from(value: Vec<B>) -> Self {
+    Self::Buffers(value.into_iter().map(Into::into).collect())
+  }
+}
+
+impl BufferDesc {
+  /// Total size in bytes of all parts
+  pub fn size(&self) -> u64 {
+    match self {
+      Self::Buffer(b) | Self::TypedBuffer(b, _) => b.size() as u64,
+      Self::BufferSize(b) => *b,
+      Self::Buffers(parts) => parts.iter().map(BufferDesc::size).sum(),
+    }
+  }
+
+  /// Offset in bytes of the described memory within its (first) `VkBuffer`
+  pub fn offset(&self) -> u64 {
+    match self {
+      Self::Buffer(b) | Self::TypedBuffer(b, _) => b.inner().offset as u64,
+      Self::BufferSize(_) => 0,
+      Self::Buffers(parts) => parts.first().map(BufferDesc::offset).unwrap_or(0),
+    }
+  }
+
+  /// The individual buffers this description consists of
+  pub fn parts(&self) -> &[BufferDesc] {
+    match self {
+      Self::Buffers(parts) => parts,
+      _ => std::slice::from_ref(self),
+    }
+  }
+
+  pub fn element_type(&self) -> Option<ElementType> {
+    match self {
+      Self::TypedBuffer(_, element_type) => Some(*element_type),
+      _ => None,
+    }
+  }
+
+  pub fn as_buffer(&self) -> Option<&Arc<dyn BufferAccess>> {
+    match self {
+      Self::Buffer(b) | Self::TypedBuffer(b, _) => Some(b),
+      _ => None,
+    }
+  }
+
+  pub fn as_buffer_size(&self) -> Option<&u64> {
+    match self {
+      Self::BufferSize(b) => Some(b),
+      _ => None,
+    }
+  }
+}
+
+/// Sizes in bytes that VkFFT expects for each buffer of a configuration. Sizes too large for a
+/// `u64` saturate at `u64::MAX`; `build` rejects such configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferSizes {
+  pub buffer: u64,
+  pub input_buffer: u64,
+  pub output_buffer: u64,
+  pub temp_buffer: u64,
+  pub kernel: u64,
+}
+
+impl BufferSizes {
+  pub fn get(&self, role: BufferRole) -> u64 {
+    match role {
+      BufferRole::Buffer => self.buffer,
+      BufferRole::InputBuffer => self.input_buffer,
+      BufferRole::OutputBuffer => self.output_buffer,
+      BufferRole::TempBuffer => self.temp_buffer,
+      BufferRole::Kernel => self.kernel,
+    }
+  }
+
+  pub fn buffer_desc(&self) -> BufferDesc {
+    BufferDesc::BufferSize(self.buffer)
+  }
+
+  pub fn input_buffer_desc(&self) -> BufferDesc {
+    BufferDesc::BufferSize(self.input_buffer)
+  }
+
+  pub fn output_buffer_desc(&self) -> BufferDesc {
+    BufferDesc::BufferSize(self.output_buffer)
+  }
+
+  pub fn temp_buffer_desc(&self) -> BufferDesc {
+    BufferDesc::BufferSize(self.temp_buffer)
+  }
+
+  pub fn kernel_desc(&self) -> BufferDesc {
+    BufferDesc::BufferSize(self.kernel)
+  }
+}
+
+/// Placement of data within a buffer, in the spirit of FFTW's advanced interface. Distances are
+/// counted in elements: complex values, or real values for unpadded (formatted) R2C data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataLayout {
+  /// Distance between neighboring elements along each axis. VkFFT requires the first axis to be
+  /// contiguous, so `strides[0]` must be 1.
+  pub strides: [u64; MAX_DIMENSIONS],
+  /// Distance between the starts of consecutive coordinate features
+  pub feature_distance: u64,
+  /// Distance between the starts of consecutive batches. VkFFT places batches directly after
+  /// the coordinate features of the previous batch, so this must be
+  /// `feature_distance * coordinate_features`.
+  pub batch_distance: u64,
+}
+
+impl DataLayout {
+  /// The densely packed layout of data with `extent` elements along each axis. Distances too
+  /// large for a `u64` saturate, so the span of such a layout overflows.
+  pub fn packed(extent: [u64; MAX_DIMENSIONS], coordinate_features: u32) -> Self {
+    let mut strides = [1u64; MAX_DIMENSIONS];
+    for axis in 1..MAX_DIMENSIONS {
+      strides[axis] = strides[axis - 1].saturating_mul(extent[axis - 1]);
+    }
+
+    let feature_distance = strides[MAX_DIMENSIONS - 1].saturating_mul(extent[MAX_DIMENSIONS - 1]);
+
+    Self {
+      strides,
+      feature_distance,
+      batch_distance: feature_distance.saturating_mul(coordinate_features as u64),
+    }
+  }
+
+  /// Number of elements from the first to one past the last element addressed by this layout,
+  /// or `None` if it doesn't fit in a `u64`
+  pub fn span(
+    &self,
+    extent: [u64; MAX_DIMENSIONS],
+    coordinate_features: u32,
+    batch_count: u32,
+  ) -> Option<u64> {
+    let counts = [coordinate_features as u64, batch_count as u64];
+    let distances = [self.feature_distance, self.batch_distance];
+
+    extent
+      .iter()
+      .chain(counts.iter())
+      .zip(self.strides.iter().chain(distances.iter()))
+      .try_fold(1u64, |span, (len, distance)| {
+        span.checked_add(len.saturating_sub(1).checked_mul(*distance)?)
+      })
+  }
+
+  /// Check that the layout is one VkFFT can address and that no two elements overlap
+  fn validate(
+    &self,
+    role: BufferRole,
+    extent: [u64; MAX_DIMENSIONS],
+    coordinate_features: u32,
+  ) -> Result<(), ConfigError> {
+    if self.strides[0] != 1 {
+      return Err(ConfigError::UnsupportedStride {
+        role,
+        axis: 0,
+        stride: self.strides[0],
+      });
+    }
+
+    let mut span = extent[0];
+    for (&len, &stride) in extent.iter().zip(self.strides.iter()).skip(1) {
+      if len > 1 && stride < span {
+        return Err(ConfigError::OverlappingLayout {
+          role,
+          layout: *self,
+        });
+      }
+      span = span.max(stride.saturating_mul(len));
+    }
+
+    if self.feature_distance < span {
+      return Err(ConfigError::OverlappingLayout {
+        role,
+        layout: *self,
+      });
+    }
+
+    let expected = self
+      .feature_distance
+      .saturating_mul(coordinate_features as u64);
+    if self.batch_distance != expected {
+      return Err(ConfigError::UnsupportedBatchDistance {
+        role,
+        batch_distance: self.batch_distance,
+        expected,
+      });
+    }
+
+    Ok(())
+  }
+
+  /// The strides in the form of VkFFT's `bufferStride` fields
+  pub(crate) fn as_sys(&self) -> [u64; MAX_DIMENSIONS] {
+    let mut res = [self.feature_distance; MAX_DIMENSIONS];
+    res[..MAX_DIMENSIONS - 1].copy_from_slice(&self.strides[1..]);
+    res
+  }
+}
+
+/// Performance parameters of the generated shaders. VkFFT picks each one from the device's
+/// properties unless it is set here, so the defaults below are only typical values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tuning {
+  /// Number of shared memory banks. Typically 32. Must be a power of two between 1 and 64.
+  pub shared_banks: Option<u32>,
+
+  /// Bytes loaded in a single coalesced memory transaction. Typically 32, or 64 on Intel. Must be
+  /// a power of two between 16 and 256.
+  pub coalesced_memory: Option<u32>,
+
+  /// Number of threads VkFFT aims to use per workgroup. Typically 128. Must be between 1 and
+  /// `max_threads`.
+  pub aim_threads: Option<u32>,
+
+  /// Maximum number of threads per workgroup. Defaults to the device's
+  /// `maxComputeWorkGroupInvocations`. Must be between 1 and 4096.
+  pub max_threads: Option<u32>,
+
+  /// Threads per subgroup. Defaults to the device's subgroup size. Must be a power of two between
+  /// 1 and 128.
+  pub warp_size: Option<u32>,
+
+  /// Factor by which registers are used in place of shared memory for large sequences. Defaults
+  /// to 1. Must be 1, 2 or 4.
+  pub register_boost: Option<u32>,
+
+  /// Also apply `register_boost` to sizes that aren't powers of two. Defaults to false.
+  pub register_boost_non_pow2: Option<bool>,
+
+  /// Register boost used by the four step algorithm. Defaults to 1. Must be 1, 2 or 4.
+  pub register_boost_4_step: Option<u32>,
+
+  /// Unshuffle the output of the four step algorithm. Defaults to true. `Some(false)` has the same
+  /// effect as `disable_reorder_four_step`.
+  pub reorder_four_step: Option<bool>,
+}
+
+impl Tuning {
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    fn check<F>(
+      parameter: &'static str,
+      value: Option<u32>,
+      valid: F,
+      expected: &'static str,
+    ) -> Result<(), ConfigError>
+    where
+      F: Fn(u32) -> bool,
+    {
+      match value {
+        Some(value) if !valid(value) => Err(ConfigError::InvalidTuning {
+          parameter,
+          value,
+          expected,
+        }),
+        _ => Ok(()),
+      }
+    }
+
+    let pow2_between = |min, max| move |v: u32| v.is_power_of_two() && (min..=max).contains(&v);
+    let max_threads = self.max_threads.unwrap_or(4096);
+
+    check(
+      "shared_banks",
+      self.shared_banks,
+      pow2_between(1, 64),
+      "a power of two between 1 and 64",
+    )?;
+    check(
+      "coalesced_memory",
+      self.coalesced_memory,
+      pow2_between(16, 256),
+      "a power of two between 16 and 256",
+    )?;
+    check(
+      "max_threads",
+      self.max_threads,
+      |v| (1..=4096).contains(&v),
+      "between 1 and 4096",
+    )?;
+    check(
+      "aim_threads",
+      self.aim_threads,
+      |v| (1..=max_threads).contains(&v),
+      "between 1 and max_threads",
+    )?;
+    check(
+      "warp_size",
+      self.warp_size,
+      pow2_between(1, 128),
+      "a power of two between 1 and 128",
+    )?;
+    check(
+      "register_boost",
+      self.register_boost,
+      |v| matches!(v, 1 | 2 | 4),
+      "1, 2 or 4",
+    )?;
+    check(
+      "register_boost_4_step",
+      self.register_boost_4_step,
+      |v| matches!(v, 1 | 2 | 4),
+      "1, 2 or 4",
+    )?;
+
+    Ok(())
+  }
+
+  fn apply(&self, config: &mut vkfft_sys::VkFFTConfiguration) {
+    if let Some(shared_banks) = self.shared_banks {
+      config.numSharedBanks = shared_banks as u64;
+    }
+
+    if let Some(coalesced_memory) = self.coalesced_memory {
+      config.coalescedMemory = coalesced_memory as u64;
+    }
+
+    if let Some(aim_threads) = self.aim_threads {
+      config.aimThreads = aim_threads as u64;
+    }
+
+    if let Some(max_threads) = self.max_threads {
+      config.maxThreadsNum = max_threads as u64;
+    }
+
+    if let Some(warp_size) = self.warp_size {
+      config.warpSize = warp_size as u64;
+    }
+
+    if let Some(register_boost) = self.register_boost {
+      config.registerBoost = register_boost as u64;
+    }
+
+    if let Some(register_boost_non_pow2) = self.register_boost_non_pow2 {
+      config.registerBoostNonPow2 = register_boost_non_pow2.into();
+    }
+
+    if let Some(register_boost_4_step) = self.register_boost_4_step {
+      config.registerBoost4Step = register_boost_4_step as u64;
+    }
+
+    if self.reorder_four_step == Some(false) {
+      config.disableReorderFourStep = true.into();
+    }
+  }
+}
+
+/// The subset of a configuration that determines how data is laid out in memory
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Layout {
+  pub size: Shape,
+  pub transform: Transform,
+  pub precision: Precision,
+  pub coordinate_features: u32,
+  pub batch_count: u32,
+  pub kernel_convolution: bool,
+  pub convolution: bool,
+  pub convolution_mode: ConvolutionMode,
+  pub kernel_count: u32,
+  pub input_formatted: bool,
+  pub output_formatted: bool,
+  pub buffer_layout: Option<DataLayout>,
+  pub input_layout: Option<DataLayout>,
+  pub output_layout: Option<DataLayout>,
+  pub inverse_return_to_input_buffer: bool,
+}
+
+impl Layout {
+  /// Whether the given role holds unpadded real values rather than complex values
+  fn is_real(&self, role: BufferRole) -> bool {
+    // Half precision memory always uses unpadded input/output buffers
+    let formatted = |formatted| formatted || self.precision == Precision::HalfMemory;
+
+    match self.transform {
+      Transform::C2c => false,
+      Transform::R2c => match role {
+        BufferRole::InputBuffer => formatted(self.input_formatted),
+        BufferRole::OutputBuffer => formatted(self.output_formatted),
+        _ => false,
+      },
+      _ => true,
+    }
+  }
+
+  /// Number of elements along each axis of one coordinate feature of one batch. The first axis
+  /// of complex R2C data only stores the `size[0] / 2 + 1` non-redundant values.
+  fn extent(&self, role: BufferRole) -> [u64; MAX_DIMENSIONS] {
+    let mut extent = self.signal_extent();
+
+    if self.transform == Transform::R2c && !self.is_real(role) {
+      extent[0] = extent[0] / 2 + 1;
+    }
+
+    extent
+  }
+
+  /// Size in bytes of a single element of the given role
+  fn element_size(&self, role: BufferRole) -> u64 {
+    let scalar_size = match role {
+      BufferRole::InputBuffer | BufferRole::OutputBuffer => self.precision.io_scalar_size(),
+      _ => self.precision.scalar_size(),
+    };
+
+    match self.is_real(role) {
+      true => scalar_size,
+      false => 2 * scalar_size,
+    }
+  }
+
+  /// Number of values along each transformed axis of the real or complex signal, ignoring the
+  /// halved extent of complex R2C data
+  pub fn signal_extent(&self) -> [u64; MAX_DIMENSIONS] {
+    self.size.padded()
+  }
+
+  /// Index in scalars of the sample at `pos` of the first feature and batch of a role holding
+  /// signal domain data. For complex data this is the real part.
+  pub fn scalar_index(&self, role: BufferRole, pos: [u64; MAX_DIMENSIONS]) -> u64 {
+    let strides = self.data_layout(role).strides;
+    let offset = |first: usize| {
+      (first..MAX_DIMENSIONS)
+        .map(|axis| pos[axis] * strides[axis])
+        .sum::<u64>()
+    };
+
+    match (self.is_real(role), self.transform) {
+      (true, _) => offset(0),
+      // The real values of an in-place R2C row share the storage of its complex values
+      (false, Transform::R2c) => pos[0] + 2 * offset(1),
+      (false, _) => 2 * offset(0),
+    }
+  }
+
+  /// The role a launch in `direction` writes its result to. The inverse reads a formatted output
+  /// buffer and writes back to `buffer`, or to the input buffer if configured to.
+  pub fn result_role(&self, direction: Direction) -> BufferRole {
+    match direction {
+      Direction::Inverse if self.inverse_return_to_input_buffer => BufferRole::InputBuffer,
+      Direction::Inverse => BufferRole::Buffer,
+      _ if self.output_formatted => BufferRole::OutputBuffer,
+      _ => BufferRole::Buffer,
+    }
+  }
+
+  /// The layout of the given role, either user specified or densely packed
+  pub fn data_layout(&self, role: BufferRole) -> DataLayout {
+    let custom = match role {
+      BufferRole::Buffer => self.buffer_layout,
+      BufferRole::InputBuffer if self.input_formatted => self.input_layout,
+      BufferRole::InputBuffer => self.buffer_layout,
+      BufferRole::OutputBuffer if self.output_formatted => self.output_layout,
+      BufferRole::OutputBuffer => self.buffer_layout,
+      _ => None,
+    };
+
+    custom.unwrap_or_else(|| DataLayout::packed(self.extent(role), self.features(role)))
+  }
+
+  /// Number of coordinate features stored in the given role. Kernels, and the buffers of an
+  /// application transforming them, hold one feature per kernel element.
+  fn features(&self, role: BufferRole) -> u32 {
+    if role == BufferRole::Kernel || self.kernel_convolution {
+      self
+        .convolution_mode
+        .kernel_features(self.coordinate_features)
+    } else {
+      self.coordinate_features
+    }
+  }
+
+  /// Number of batches stored in the given role. Kernels are stored one after another, and so
+  /// are the results of convolving the input with each of them.
+  fn batches(&self, role: BufferRole) -> Option<u32> {
+    if role == BufferRole::Kernel || self.kernel_convolution {
+      return Some(self.kernel_count);
+    }
+
+    match role {
+      BufferRole::InputBuffer => Some(self.batch_count),
+      _ if self.convolution => self.batch_count.checked_mul(self.kernel_count),
+      _ => Some(self.batch_count),
+    }
+  }
+
+  /// Size in bytes of the given role, or `None` if it doesn't fit in a `u64`
+  fn size_of(&self, role: BufferRole) -> Option<u64> {
+    let span =
+      self
+        .data_layout(role)
+        .span(self.extent(role), self.features(role), self.batches(role)?)?;
+
+    span.checked_mul(self.element_size(role))
+  }
+
+  /// Whether some buffer holds more scalars than VkFFT can index with 32-bit integers
+  pub fn requires_64_bit_indexing(&self) -> bool {
+    BufferRole::ALL
+      .iter()
+      .any(|&role| match self.size_of(role) {
+        Some(size) => size / self.offset_alignment(role) > u32::MAX as u64,
+        None => true,
+      })
+  }
+
+  /// Byte alignment VkFFT requires of a buffer offset, which it addresses in whole values
+  pub fn offset_alignment(&self, role: BufferRole) -> u64 {
+    match role {
+      BufferRole::InputBuffer | BufferRole::OutputBuffer => self.precision.io_scalar_size(),
+      _ => self.precision.scalar_size(),
+    }
+  }
+
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    if self.size.is_empty() {
+      return Err(ConfigError::InvalidFftDim { fft_dim: 0 });
+    }
+
+    let dims = self.size.as_slice();
+
+    if let Some(axis) = dims.iter().position(|&len| len == 0) {
+      return Err(ConfigError::ZeroSize { axis });
+    }
+
+    if self.coordinate_features == 0 {
+      return Err(ConfigError::ZeroCoordinateFeatures);
+    }
+
+    if self.batch_count == 0 {
+      return Err(ConfigError::ZeroBatchCount);
+    }
+
+    if self.kernel_count == 0 {
+      return Err(ConfigError::ZeroKernelCount);
+    }
+
+    if self.transform == Transform::R2c && self.size[0] % 2 != 0 {
+      return Err(ConfigError::OddR2cSize { size: self.size[0] });
+    }
+
+    if self.transform.is_dct() {
+      if let Precision::Half | Precision::HalfMemory = self.precision {
+        return Err(ConfigError::UnsupportedTransformPrecision {
+          transform: self.transform,
+          precision: self.precision,
+        });
+      }
+
+      // DCT-I divides by one less than the size
+      if self.transform == Transform::Dct1 {
+        if let Some(axis) = dims.iter().position(|&len| len < 2) {
+          return Err(ConfigError::InvalidTransformSize {
+            transform: self.transform,
+            axis,
+            size: self.size[axis],
+          });
+        }
+      }
+    }
+
+    let layouts = [
+      (BufferRole::Buffer, self.buffer_layout, true),
+      (
+        BufferRole::InputBuffer,
+        self.input_layout,
+        self.input_formatted,
+      ),
+      (
+        BufferRole::OutputBuffer,
+        self.output_layout,
+        self.output_formatted,
+      ),
+    ];
+
+    for (role, layout, formatted) in layouts.iter() {
+      if let Some(layout) = layout {
+        if !formatted {
+          return Err(ConfigError::LayoutRequiresFormatted { role: *role });
+        }
+
+        layout.validate(*role, self.extent(*role), self.features(*role))?;
+      }
+    }
+
+    for &role in BufferRole::ALL.iter() {
+      if self.size_of(role).is_none() {
+        return Err(ConfigError::SizeOverflow {
+          role,
+          size: self.size,
+        });
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn buffer_sizes(&self) -> BufferSizes {
+    let size_of = |role| self.size_of(role).unwrap_or(u64::MAX);
+
+    BufferSizes {
+      buffer: size_of(BufferRole::Buffer),
+      input_buffer: size_of(BufferRole::InputBuffer),
+      output_buffer: size_of(BufferRole::OutputBuffer),
+      temp_buffer: size_of(BufferRole::TempBuffer),
+      kernel: size_of(BufferRole::Kernel),
+    }
+  }
+
+  /// Write the sizes, strides and formats VkFFT reads the buffers with into `config`
+  pub fn apply(&self, config: &mut vkfft_sys::VkFFTConfiguration) {
+    config.FFTdim = self.size.len() as u64;
+    config.size = self.size.padded();
+
+    // VkFFT indexes buffers with 32-bit integers unless told otherwise
+    config.useUint64 = self.requires_64_bit_indexing().into();
+
+    config.kernelConvolution = self.kernel_convolution.into();
+    config.performR2C = (self.transform == Transform::R2c).into();
+    config.performDCT = self.transform.dct_type();
+    config.coordinateFeatures = self.features(BufferRole::Buffer) as u64;
+
+    if self.convolution {
+      config.performConvolution = true.into();
+      config.matrixConvolution = self
+        .convolution_mode
+        .matrix_size(self.coordinate_features)
+        .into();
+      config.symmetricKernel = (self.convolution_mode == ConvolutionMode::SymmetricMatrix).into();
+      config.numberKernels = self.kernel_count as u64;
+    }
+
+    config.inverseReturnToInputBuffer = self.inverse_return_to_input_buffer.into();
+
+    if let Some(layout) = &self.buffer_layout {
+      config.bufferStride = layout.as_sys();
+    }
+
+    if let Some(layout) = &self.input_layout {
+      config.inputBufferStride = layout.as_sys();
+    }
+
+    if let Some(layout) = &self.output_layout {
+      config.outputBufferStride = layout.as_sys();
+    }
+
+    config.isInputFormatted = self.input_formatted.into();
+    config.isOutputFormatted = self.output_formatted.into();
+
+    match self.precision {
+      Precision::Double => config.doublePrecision = true.into(),
+      Precision::Half => config.halfPrecision = true.into(),
+      Precision::HalfMemory => {
+        config.halfPrecisionMemoryOnly = true.into();
+        config.isInputFormatted = true.into();
+        config.isOutputFormatted = true.into();
+      }
+      _ => {}
+    }
+
+    // An application transforming kernels stores them as batches
+    config.numberBatches = match self.kernel_convolution {
+      true => self.kernel_count,
+      false => self.batch_count,
+    } as u64;
+  }
+}
+
+/// The settings of a configuration besides its layout, its devices and its buffers
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Options {
+  pub normalization: Normalization,
+  pub zero_padding: [bool; MAX_DIMENSIONS],
+  pub omit_dimension: [bool; MAX_DIMENSIONS],
+  pub zeropad_left: [u64; MAX_DIMENSIONS],
+  pub zeropad_right: [u64; MAX_DIMENSIONS],
+  pub frequency_zero_padding: bool,
+  pub convolution_op: ConvolutionOp,
+  pub disable_reorder_four_step: bool,
+  pub tuning: Tuning,
+  pub direction: Direction,
+  pub save_plan: bool,
+  pub keep_shader_code: bool,
+  pub print_memory_layout: bool,
+}
+
+impl Options {
+  /// Write these settings into `config`
+  pub fn apply(&self, config: &mut vkfft_sys::VkFFTConfiguration) {
+    config.normalize = (self.normalization == Normalization::Inverse).into();
+
+    config.performZeropadding = self.zero_padding.map(u64::from);
+    config.omitDimension = self.omit_dimension.map(u64::from);
+    config.fft_zeropad_left = self.zeropad_left;
+    config.fft_zeropad_right = self.zeropad_right;
+    config.frequencyZeroPadding = self.frequency_zero_padding.into();
+
+    // Applications that don't convolve are validated to use the plain convolution, which sets
+    // nothing
+    self.convolution_op.apply(config);
+
+    config.disableReorderFourStep = self.disable_reorder_four_step.into();
+    self.tuning.apply(config);
+
+    config.saveApplicationToString = self.save_plan.into();
+    config.keepShaderCode = self.keep_shader_code.into();
+    config.printMemoryLayout = self.print_memory_layout.into();
+
+    match self.direction {
+      Direction::Forward => config.makeForwardPlanOnly = true.into(),
+      Direction::Inverse => config.makeInversePlanOnly = true.into(),
+      Direction::Both => {}
+    }
+  }
+}
+
+pub struct Config<'a> {
+  /// Size of each transformed axis
+  pub size: Shape,
+
+  pub physical_device: PhysicalDevice<'a>,
+  pub device: Arc<Device>,
+  pub queue: Arc<Queue>,
+  pub fence: &'a Fence,
+  pub command_pool: Arc<UnsafeCommandPool>,
+
+  pub buffer: Option<BufferDesc>,
+  pub input_buffer: Option<BufferDesc>,
+  pub output_buffer: Option<BufferDesc>,
+  pub temp_buffer: Option<BufferDesc>,
+  pub kernel: Option<BufferDesc>,
+
+  /// How the results of the transforms are scaled
+  pub normalization: Normalization,
+
+  /// Don't read some data/perform computations if some input sequences are zeropadded for each axis
+  pub zero_padding: [bool; MAX_DIMENSIONS],
+
+  /// Don't transform along these axes. They still take part in the data layout.
+  pub omit_dimension: [bool; MAX_DIMENSIONS],
+
+  /// Specify start boundary of zero block in the system for each axis
+  pub zeropad_left: [u64; MAX_DIMENSIONS],
+
+  /// Specify end boundary of zero block in the system for each axis
+  pub zeropad_right: [u64; MAX_DIMENSIONS],
+
+  /// The zero blocks are in the frequency domain rather than in the input of the forward transform
+  pub frequency_zero_padding: bool,
+
+  /// Specify if this application is used to create kernel for convolution, so it has the same properties
+  pub kernel_convolution: bool,
+
+  /// Perform convolution in this application (0 - off, 1 - on). Disables reorderFourStep parameter
+  pub convolution: bool,
+
+  /// Kind of transform to perform
+  pub transform: Transform,
+
+  /// C - coordinate, or dimension of features vector. In matrix convolution - size of vector
+  pub coordinate_features: u32,
+
+  /// Disables unshuffling of four step algorithm. Requires `temp_buffer` allocation.
+  pub disable_reorder_four_step: bool,
+
+  /// Used to perform multiple batches of initial data
+  pub batch_count: Option<u32>,
+
+  pub precision: Precision,
+
+  /// Directions the application is compiled for
+  pub direction: Direction,
+
+  /// Switches from calculating sincos to using precomputed LUT tables
+  pub use_lut: bool,
+
+  /// Performance parameters, left to VkFFT unless set
+  pub tuning: Tuning,
+
+  /// How the kernel combines coordinate features
+  pub convolution_mode: ConvolutionMode,
+
+  /// What a convolution computes from the spectra of the input and the kernel
+  pub convolution_op: ConvolutionOp,
+
+  /// Number of kernels applied in one convolution pass, stored one after another in `kernel`
+  pub kernel_count: u32,
+
+  /// specify if input buffer is padded - false is padded, true is not padded.
+  /// For example if it is not padded for R2C if out-of-place mode is selected
+  /// (only if numberBatches==1 and numberKernels==1)
+  pub input_formatted: Option<bool>,
+
+  /// specify if output buffer is padded - false is padded, true is not padded.
+  /// For example if it is not padded for R2C if out-of-place mode is selected
+  /// (only if numberBatches==1 and numberKernels==1)
+  pub output_formatted: Option<bool>,
+
+  /// Custom layout of `buffer`. Densely packed if not specified.
+  pub buffer_layout: Option<DataLayout>,
+
+  /// Custom layout of the input buffer. Requires `input_formatted`.
+  pub input_layout: Option<DataLayout>,
+
+  /// Custom layout of the output buffer. Requires `output_formatted`.
+  pub output_layout: Option<DataLayout>,
+
+  /// Keep the compiled plan so it can be saved and reloaded without compiling shaders again
+  pub save_plan: bool,
+
+  /// Keep the generated shader sources for debugging
+  pub keep_shader_code: bool,
+
+  /// Print the memory layout VkFFT chose for each dispatch to stdout
+  pub print_memory_layout: bool,
+
+  /// Write the result of the inverse to the input buffer instead of `buffer`. Requires
+  /// `input_formatted`.
+  pub inverse_return_to_input_buffer: bool,
+}
+
+/// Size of each transformed axis, for between 0 and [`MAX_DIMENSIONS`] axes. Dereferences to
+/// the sizes of the axes it holds.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Shape {
+  dims: [u64; MAX_DIMENSIONS],
+  len: usize,
+}
+
+impl Shape {
+  pub fn new(dims: &[u64]) -> Result<Self, ConfigError> {
+    if dims.len() > MAX_DIMENSIONS {
+      return Err(ConfigError::TooManyAxes {
+        setting: "dim",
+        count: dims.len(),
+      });
+    }
+
+    let mut res = Self {
+      dims: [1; MAX_DIMENSIONS],
+      len: dims.len(),
+    };
+    res.dims[..dims.len()].copy_from_slice(dims);
+
+    Ok(res)
+  }
+
+  pub fn as_slice(&self) -> &[u64] {
+    &self.dims[..self.len]
+  }
+
+  /// The sizes of all axes VkFFT addresses, where axes past the shape have size 1
+  pub fn padded(&self) -> [u64; MAX_DIMENSIONS] {
+    self.dims
+  }
+}
+
+impl Default for Shape {
+  /// A single axis of size 1
+  fn default() -> Self {
+    Self {
+      dims: [1; MAX_DIMENSIONS],
+      len: 1,
+    }
+  }
+}
+
+impl std::ops::Deref for Shape {
+  type Target = [u64];
+
+  fn deref(&self) -> &[u64] {
+    self.as_slice()
+  }
+}
+
+impl fmt::Debug for Shape {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.as_slice()).finish()
+  }
+}
+
+impl TryFrom<&[u64]> for Shape {
+  type Error = ConfigError;
+
+  fn try_from(dims: &[u64]) -> Result<Self, ConfigError> {
+    Self::new(dims)
+  }
+}
+
+/// Identifies one of the buffers a configuration or launch refers to
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferRole {
+  #[display(fmt = "buffer")]
+  Buffer,
+  #[display(fmt = "input buffer")]
+  InputBuffer,
+  #[display(fmt = "output buffer")]
+  OutputBuffer,
+  #[display(fmt = "temp buffer")]
+  TempBuffer,
+  #[display(fmt = "kernel")]
+  Kernel,
+}
+
+impl BufferRole {
+  pub const ALL: [BufferRole; 5] = [
+    Self::Buffer,
+    Self::InputBuffer,
+    Self::OutputBuffer,
+    Self::TempBuffer,
+    Self::Kernel,
+  ];
+}
+
+#[derive(Display, Debug, Error)]
+pub enum ConfigError {
+  #[display(
+    fmt = "fft_dim must be between 1 and {} (got {})",
+    MAX_DIMENSIONS,
+    fft_dim
+  )]
+  InvalidFftDim { fft_dim: u32 },
+  #[display(
+    fmt = "{} has {} axes but VkFFT supports at most {}",
+    setting,
+    count,
+    MAX_DIMENSIONS
+  )]
+  TooManyAxes { setting: &'static str, count: usize },
+  #[display(fmt = "size of axis {} is zero", axis)]
+  ZeroSize { axis: usize },
+  #[display(fmt = "{} of size {:?} doesn't fit in 64 bits", role, size)]
+  SizeOverflow { role: BufferRole, size: Shape },
+  #[display(fmt = "R2C requires an even size along the first axis (got {})", size)]
+  OddR2cSize { size: u64 },
+  #[display(
+    fmt = "a typed builder requires {:?} precision, but it was changed to {:?}",
+    expected,
+    found
+  )]
+  TypedPrecisionChanged {
+    expected: Precision,
+    found: Precision,
+  },
+  #[display(fmt = "{} of a typed builder must have a known element type", role)]
+  UntypedBuffer { role: BufferRole },
+  #[display(fmt = "{} is not supported in {:?} precision", transform, precision)]
+  UnsupportedTransformPrecision {
+    transform: Transform,
+    precision: Precision,
+  },
+  #[display(
+    fmt = "{} does not support size {} along axis {}",
+    transform,
+    size,
+    axis
+  )]
+  InvalidTransformSize {
+    transform: Transform,
+    axis: usize,
+    size: u64,
+  },
+  #[display(fmt = "{} cannot be used with convolutions", transform)]
+  UnsupportedConvolutionTransform { transform: Transform },
+  #[display(fmt = "coordinate_features must be at least 1")]
+  ZeroCoordinateFeatures,
+  #[display(fmt = "batch_count must be at least 1")]
+  ZeroBatchCount,
+  #[display(fmt = "convolution requires a kernel")]
+  ConvolutionWithoutKernel,
+  #[display(
+    fmt = "{:?} convolutions don't support {} coordinate features",
+    mode,
+    coordinate_features
+  )]
+  UnsupportedConvolutionMode {
+    mode: ConvolutionMode,
+    coordinate_features: u32,
+  },
+  #[display(fmt = "{:?} requires convolution to be enabled", op)]
+  ConvolutionOpWithoutConvolution { op: ConvolutionOp },
+  #[display(fmt = "kernel_count must be at least 1")]
+  ZeroKernelCount,
+  #[display(
+    fmt = "convolving with {} kernels requires a batch count of 1",
+    kernel_count
+  )]
+  MultipleKernelsWithBatches { kernel_count: u32 },
+  #[display(
+    fmt = "transforming {} kernels requires a batch count of {} (got {})",
+    kernel_count,
+    kernel_count,
+    batch_count
+  )]
+  KernelBatchMismatch { kernel_count: u32, batch_count: u32 },
+  #[display(
+    fmt = "zero padding [{}, {}) of axis {} is outside of its size {}",
+    left,
+    right,
+    axis,
+    size
+  )]
+  InvalidZeroPadding {
+    axis: usize,
+    left: u64,
+    right: u64,
+    size: u64,
+  },
+  #[display(
+    fmt = "cannot keep {} values from {} of axis {} with size {}, the skipped values must be one block that doesn't wrap around",
+    len,
+    start,
+    axis,
+    size
+  )]
+  InvalidPruning {
+    axis: usize,
+    start: u64,
+    len: u64,
+    size: u64,
+  },
+  #[display(
+    fmt = "pruned axis {} is out of range for {} dimensions",
+    axis,
+    fft_dim
+  )]
+  PrunedAxisOutOfRange { axis: usize, fft_dim: usize },
+  #[display(fmt = "every axis is omitted, at least one must be transformed")]
+  AllDimensionsOmitted,
+  #[display(fmt = "the first axis of an R2C transform cannot be omitted")]
+  OmittedR2cDimension,
+  #[display(fmt = "omitted axes are not supported with convolutions")]
+  OmittedDimensionWithConvolution,
+  #[display(
+    fmt = "{} is {} bytes but the layout requires {} bytes",
+    role,
+    size,
+    required
+  )]
+  BufferTooSmall {
+    role: BufferRole,
+    size: u64,
+    required: u64,
+  },
+  #[display(
+    fmt = "offset {} of {} is not a multiple of {} bytes",
+    offset,
+    role,
+    alignment
+  )]
+  MisalignedOffset {
+    role: BufferRole,
+    offset: u64,
+    alignment: u64,
+  },
+  #[display(
+    fmt = "{} holds {} values but the precision requires {}",
+    role,
+    found,
+    expected
+  )]
+  ElementTypeMismatch {
+    role: BufferRole,
+    expected: ElementType,
+    found: ElementType,
+  },
+  #[display(
+    fmt = "stride {} of axis {} of {} is not supported",
+    stride,
+    axis,
+    role
+  )]
+  UnsupportedStride {
+    role: BufferRole,
+    axis: usize,
+    stride: u64,
+  },
+  #[display(fmt = "elements of {} overlap in {:?}", role, layout)]
+  OverlappingLayout {
+    role: BufferRole,
+    layout: DataLayout,
+  },
+  #[display(
+    fmt = "batch distance {} of {} must be {}",
+    batch_distance,
+    role,
+    expected
+  )]
+  UnsupportedBatchDistance {
+    role: BufferRole,
+    batch_distance: u64,
+    expected: u64,
+  },
+  #[display(fmt = "a custom layout of {} requires it to be formatted", role)]
+  LayoutRequiresFormatted { role: BufferRole },
+  #[display(fmt = "{} is split into an empty list of buffers", role)]
+  EmptyBufferList { role: BufferRole },
+  #[display(
+    fmt = "{} must be split into either buffers or buffer sizes, not both",
+    role
+  )]
+  InvalidBufferList { role: BufferRole },
+  #[display(
+    fmt = "part {} of {} has offset {}, but only the first part may",
+    index,
+    role,
+    offset
+  )]
+  OffsetInBufferPart {
+    role: BufferRole,
+    index: usize,
+    offset: u64,
+  },
+  #[display(
+    fmt = "part {} of {} is {} bytes, which is not a multiple of {} bytes",
+    index,
+    role,
+    size,
+    alignment
+  )]
+  MisalignedBufferPart {
+    role: BufferRole,
+    index: usize,
+    size: u64,
+    alignment: u64,
+  },
+  #[display(fmt = "half precision memory requires formatted input and output buffers")]
+  HalfMemoryRequiresFormatted,
+  #[display(fmt = "returning the inverse to the input buffer requires a formatted input buffer")]
+  InverseReturnRequiresFormattedInput,
+  #[display(
+    fmt = "{:?} normalization is only supported for C2C and R2C transforms without convolution",
+    normalization
+  )]
+  UnsupportedNormalization { normalization: Normalization },
+  #[display(
+    fmt = "{:?} normalization is not supported in {:?} precision",
+    normalization,
+    precision
+  )]
+  UnsupportedNormalizationPrecision {
+    normalization: Normalization,
+    precision: Precision,
+  },
+  #[display(
+    fmt = "tuning parameter {} is {}, expected {}",
+    parameter,
+    value,
+    expected
+  )]
+  InvalidTuning {
+    parameter: &'static str,
+    value: u32,
+    expected: &'static str,
+  },
+  #[display(fmt = "reorder_four_step is enabled but so is disable_reorder_four_step")]
+  ConflictingReorderFourStep,
 }
 
 pub(crate) struct KeepAlive {
@@ -460,32 +2262,118 @@ pub(crate) struct KeepAlive {
   pub queue: Arc<Queue>,
   pub command_pool: Arc<UnsafeCommandPool>,
 
-  pub buffer: Option<Arc<dyn BufferAccess>>,
-  pub input_buffer: Option<Arc<dyn BufferAccess>>,
-  pub output_buffer: Option<Arc<dyn BufferAccess>>,
-  pub temp_buffer: Option<Arc<dyn BufferAccess>>,
-  pub kernel: Option<Arc<dyn BufferAccess>>,
+  pub buffers: Vec<Arc<dyn BufferAccess>>,
+}
+
+/// Sizes and handles of the buffers of one role, in the form VkFFT reads them
+pub(crate) struct BufferGuard {
+  pub(crate) sizes: Vec<u64>,
+  pub(crate) buffers: Vec<vk_sys::Buffer>,
+  pub(crate) offset: u64,
+}
+
+impl BufferGuard {
+  fn new(desc: Option<&BufferDesc>) -> Self {
+    let parts = desc.map(BufferDesc::parts).unwrap_or(&[]);
+
+    Self {
+      sizes: parts.iter().map(|p| p.size()).collect(),
+      buffers: parts
+        .iter()
+        .filter_map(BufferDesc::as_buffer)
+        .map(|b| b.inner().buffer.internal_object().value())
+        .collect(),
+      offset: desc.map(BufferDesc::offset).unwrap_or(0),
+    }
+  }
 }
 
 #[repr(C)]
 pub(crate) struct ConfigGuard {
   pub(crate) keep_alive: KeepAlive,
   pub(crate) config: vkfft_sys::VkFFTConfiguration,
+  pub(crate) layout: Layout,
+  pub(crate) direction: Direction,
+  pub(crate) convolution_op: ConvolutionOp,
+  pub(crate) scale: Option<f64>,
   pub(crate) physical_device: vk_sys::PhysicalDevice,
   pub(crate) device: vk_sys::Device,
   pub(crate) queue: vk_sys::Queue,
   pub(crate) command_pool: vk_sys::CommandPool,
   pub(crate) fence: vk_sys::Fence,
-  pub(crate) buffer_size: u64,
-  pub(crate) buffer: Option<vk_sys::Buffer>,
-  pub(crate) input_buffer_size: u64,
-  pub(crate) input_buffer: Option<vk_sys::Buffer>,
-  pub(crate) output_buffer_size: u64,
-  pub(crate) output_buffer: Option<vk_sys::Buffer>,
-  pub(crate) temp_buffer_size: u64,
-  pub(crate) temp_buffer: Option<vk_sys::Buffer>,
-  pub(crate) kernel_size: u64,
-  pub(crate) kernel: Option<vk_sys::Buffer>,
+  pub(crate) buffer: BufferGuard,
+  pub(crate) input_buffer: BufferGuard,
+  pub(crate) output_buffer: BufferGuard,
+  pub(crate) temp_buffer: BufferGuard,
+  pub(crate) kernel: BufferGuard,
+}
+
+impl ConfigGuard {
+  pub(crate) fn buffer_guard(&self, role: BufferRole) -> &BufferGuard {
+    match role {
+      BufferRole::Buffer => &self.buffer,
+      BufferRole::InputBuffer => &self.input_buffer,
+      BufferRole::OutputBuffer => &self.output_buffer,
+      BufferRole::TempBuffer => &self.temp_buffer,
+      BufferRole::Kernel => &self.kernel,
+    }
+  }
+
+  pub(crate) fn summary(&self) -> crate::error::ConfigSummary {
+    crate::error::ConfigSummary {
+      size: self.layout.size,
+      transform: self.layout.transform,
+      precision: self.layout.precision,
+      direction: self.direction,
+      coordinate_features: self.layout.coordinate_features,
+      batch_count: self.layout.batch_count,
+    }
+  }
+}
+
+impl<'a> fmt::Debug for Config<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Config")
+      .field("size", &self.size)
+      .field("physical_device", &self.physical_device.name())
+      .field("buffer", &self.buffer)
+      .field("input_buffer", &self.input_buffer)
+      .field("output_buffer", &self.output_buffer)
+      .field("temp_buffer", &self.temp_buffer)
+      .field("kernel", &self.kernel)
+      .field("normalization", &self.normalization)
+      .field("zero_padding", &self.zero_padding)
+      .field("omit_dimension", &self.omit_dimension)
+      .field("zeropad_left", &self.zeropad_left)
+      .field("zeropad_right", &self.zeropad_right)
+      .field("frequency_zero_padding", &self.frequency_zero_padding)
+      .field("kernel_convolution", &self.kernel_convolution)
+      .field("convolution", &self.convolution)
+      .field("transform", &self.transform)
+      .field("coordinate_features", &self.coordinate_features)
+      .field("disable_reorder_four_step", &self.disable_reorder_four_step)
+      .field("batch_count", &self.batch_count)
+      .field("precision", &self.precision)
+      .field("direction", &self.direction)
+      .field("use_lut", &self.use_lut)
+      .field("tuning", &self.tuning)
+      .field("convolution_mode", &self.convolution_mode)
+      .field("convolution_op", &self.convolution_op)
+      .field("kernel_count", &self.kernel_count)
+      .field("input_formatted", &self.input_formatted)
+      .field("output_formatted", &self.output_formatted)
+      .field("buffer_layout", &self.buffer_layout)
+      .field("input_layout", &self.input_layout)
+      .field("output_layout", &self.output_layout)
+      .field("save_plan", &self.save_plan)
+      .field("keep_shader_code", &self.keep_shader_code)
+      .field("print_memory_layout", &self.print_memory_layout)
+      .field(
+        "inverse_return_to_input_buffer",
+        &self.inverse_return_to_input_buffer,
+      )
+      .finish_non_exhaustive()
+  }
 }
 
 impl<'a> Config<'a> {
@@ -493,7 +2381,14 @@ impl<'a> Config<'a> {
     ConfigBuilder::new()
   }
 
-  pub fn buffer_size(&self) -> usize {
+  pub fn typed_builder<T>() -> TypedConfigBuilder<'a, T>
+  where
+    T: Scalar,
+  {
+    TypedConfigBuilder::new()
+  }
+
+  pub fn buffer_size(&self) -> u64 {
     self.buffer.as_ref().map(|b| b.size()).unwrap_or(0)
   }
 
@@ -501,6 +2396,49 @@ impl<'a> Config<'a> {
     self.buffer.as_ref()
   }
 
+  pub(crate) fn layout(&self) -> Layout {
+    Layout {
+      size: self.size,
+      transform: self.transform,
+      precision: self.precision,
+      coordinate_features: self.coordinate_features,
+      batch_count: self.batch_count.unwrap_or(1),
+      kernel_convolution: self.kernel_convolution,
+      convolution: self.convolution,
+      convolution_mode: self.convolution_mode,
+      kernel_count: self.kernel_count,
+      input_formatted: self.input_formatted.unwrap_or(false),
+      output_formatted: self.output_formatted.unwrap_or(false),
+      buffer_layout: self.buffer_layout,
+      input_layout: self.input_layout,
+      output_layout: self.output_layout,
+      inverse_return_to_input_buffer: self.inverse_return_to_input_buffer,
+    }
+  }
+
+  pub(crate) fn options(&self) -> Options {
+    Options {
+      normalization: self.normalization,
+      zero_padding: self.zero_padding,
+      omit_dimension: self.omit_dimension,
+      zeropad_left: self.zeropad_left,
+      zeropad_right: self.zeropad_right,
+      frequency_zero_padding: self.frequency_zero_padding,
+      convolution_op: self.convolution_op,
+      disable_reorder_four_step: self.disable_reorder_four_step,
+      tuning: self.tuning,
+      direction: self.direction,
+      save_plan: self.save_plan,
+      keep_shader_code: self.keep_shader_code,
+      print_memory_layout: self.print_memory_layout,
+    }
+  }
+
+  /// Compute the buffer sizes required by this configuration's layout
+  pub fn buffer_sizes(&self) -> BufferSizes {
+    self.layout().buffer_sizes()
+  }
+
   pub fn temp_buffer(&self) -> Option<&BufferDesc> {
     self.temp_buffer.as_ref()
   }
@@ -518,7 +2456,15 @@ impl<'a> Config<'a> {
   }
 
   pub fn symmetric_kernel(&self) -> bool {
-    self.kernel_convolution
+    self.convolution_mode == ConvolutionMode::SymmetricMatrix
+  }
+
+  pub fn convolution_mode(&self) -> ConvolutionMode {
+    self.convolution_mode
+  }
+
+  pub fn convolution_op(&self) -> ConvolutionOp {
+    self.convolution_op
   }
 
   pub fn convolution(&self) -> bool {
@@ -526,11 +2472,35 @@ impl<'a> Config<'a> {
   }
 
   pub fn r2c(&self) -> bool {
-    self.r2c
+    self.transform == Transform::R2c
+  }
+
+  pub fn transform(&self) -> Transform {
+    self.transform
   }
 
   pub fn normalize(&self) -> bool {
-    self.normalize
+    self.normalization == Normalization::Inverse
+  }
+
+  pub fn normalization(&self) -> Normalization {
+    self.normalization
+  }
+
+  /// Number of values of the transformed axes, which normalization divides by
+  pub fn transform_len(&self) -> u64 {
+    self
+      .size
+      .iter()
+      .zip(self.omit_dimension.iter())
+      .filter(|(_, &omitted)| !omitted)
+      .map(|(&len, _)| len)
+      .product()
+  }
+
+  /// Factor the result of every launch is multiplied by after VkFFT's own normalization
+  pub(crate) fn scale(&self) -> Option<f64> {
+    self.normalization.scale(self.transform_len())
   }
 
   pub fn coordinate_features(&self) -> u32 {
@@ -545,176 +2515,1169 @@ impl<'a> Config<'a> {
     self.use_lut
   }
 
+  /// Check the configuration for mistakes VkFFT would otherwise fail on, or not detect at all
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    let layout = self.layout();
+    layout.validate()?;
+
+    if self.convolution && self.kernel.is_none() {
+      return Err(ConfigError::ConvolutionWithoutKernel);
+    }
+
+    if (self.convolution || self.kernel_convolution) && self.transform.is_dct() {
+      return Err(ConfigError::UnsupportedConvolutionTransform {
+        transform: self.transform,
+      });
+    }
+
+    if (self.convolution || self.kernel_convolution)
+      && !self.convolution_mode.supports(self.coordinate_features)
+    {
+      return Err(ConfigError::UnsupportedConvolutionMode {
+        mode: self.convolution_mode,
+        coordinate_features: self.coordinate_features,
+      });
+    }
+
+    if !self.convolution && self.convolution_op != ConvolutionOp::Convolution {
+      return Err(ConfigError::ConvolutionOpWithoutConvolution {
+        op: self.convolution_op,
+      });
+    }
+
+    if self.convolution && self.kernel_count > 1 && self.batch_count.unwrap_or(1) != 1 {
+      return Err(ConfigError::MultipleKernelsWithBatches {
+        kernel_count: self.kernel_count,
+      });
+    }
+
+    // An application transforming kernels stores them as batches
+    if let (true, Some(batch_count)) = (self.kernel_convolution, self.batch_count) {
+      if batch_count != self.kernel_count {
+        return Err(ConfigError::KernelBatchMismatch {
+          kernel_count: self.kernel_count,
+          batch_count,
+        });
+      }
+    }
+
+    for axis in 0..self.size.len() {
+      let (left, right) = (self.zeropad_left[axis], self.zeropad_right[axis]);
+      if self.zero_padding[axis] && (left > right || right > self.size[axis]) {
+        return Err(ConfigError::InvalidZeroPadding {
+          axis,
+          left,
+          right,
+          size: self.size[axis],
+        });
+      }
+    }
+
+    Self::validate_omit_dimension(
+      self.omit_dimension,
+      &layout,
+      self.convolution || self.kernel_convolution,
+    )?;
+
+    if self.precision == Precision::HalfMemory
+      && (self.input_formatted == Some(false) || self.output_formatted == Some(false))
+    {
+      return Err(ConfigError::HalfMemoryRequiresFormatted);
+    }
+
+    if self.inverse_return_to_input_buffer && self.input_formatted != Some(true) {
+      return Err(ConfigError::InverseReturnRequiresFormattedInput);
+    }
+
+    self.normalization.validate(&layout)?;
+
+    self.tuning.validate()?;
+
+    if self.disable_reorder_four_step && self.tuning.reorder_four_step == Some(true) {
+      return Err(ConfigError::ConflictingReorderFourStep);
+    }
+
+    let required = layout.buffer_sizes();
+    let buffers = [
+      (BufferRole::Buffer, &self.buffer, required.buffer),
+      (
+        BufferRole::InputBuffer,
+        &self.input_buffer,
+        required.input_buffer,
+      ),
+      (
+        BufferRole::OutputBuffer,
+        &self.output_buffer,
+        required.output_buffer,
+      ),
+      (
+        BufferRole::TempBuffer,
+        &self.temp_buffer,
+        required.temp_buffer,
+      ),
+      (BufferRole::Kernel, &self.kernel, required.kernel),
+    ];
+
+    for (role, desc, required) in buffers.iter() {
+      if let Some(desc) = desc {
+        Self::validate_parts(*role, desc, &layout)?;
+
+        if desc.size() < *required {
+          return Err(ConfigError::BufferTooSmall {
+            role: *role,
+            size: desc.size(),
+            required: *required,
+          });
+        }
+
+        let alignment = layout.offset_alignment(*role);
+        if desc.offset() % alignment != 0 {
+          return Err(ConfigError::MisalignedOffset {
+            role: *role,
+            offset: desc.offset(),
+            alignment,
+          });
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  fn validate_omit_dimension(
+    omit_dimension: [bool; MAX_DIMENSIONS],
+    layout: &Layout,
+    convolution: bool,
+  ) -> Result<(), ConfigError> {
+    let omitted = &omit_dimension[..layout.size.len()];
+
+    if omitted.iter().all(|&o| o) {
+      return Err(ConfigError::AllDimensionsOmitted);
+    }
+
+    if layout.transform == Transform::R2c && omitted[0] {
+      return Err(ConfigError::OmittedR2cDimension);
+    }
+
+    if convolution && omitted.iter().any(|&o| o) {
+      return Err(ConfigError::OmittedDimensionWithConvolution);
+    }
+
+    Ok(())
+  }
+
+  fn validate_parts(
+    role: BufferRole,
+    desc: &BufferDesc,
+    layout: &Layout,
+  ) -> Result<(), ConfigError> {
+    let parts = desc.parts();
+
+    if parts.is_empty() {
+      return Err(ConfigError::EmptyBufferList { role });
+    }
+
+    let nested = parts.iter().any(|p| matches!(p, BufferDesc::Buffers(_)));
+    let handles = parts.iter().filter(|p| p.as_buffer().is_some()).count();
+    if nested || (handles != 0 && handles != parts.len()) {
+      return Err(ConfigError::InvalidBufferList { role });
+    }
+
+    for (index, part) in parts.iter().enumerate() {
+      if let Some(found) = part.element_type() {
+        let expected = layout.precision.element_type(role);
+        if found != expected {
+          return Err(ConfigError::ElementTypeMismatch {
+            role,
+            expected,
+            found,
+          });
+        }
+      }
+
+      if index > 0 && part.offset() != 0 {
+        return Err(ConfigError::OffsetInBufferPart {
+          role,
+          index,
+          offset: part.offset(),
+        });
+      }
+
+      // Every part but the last must end on an element boundary
+      let alignment = layout.element_size(role);
+      if index + 1 < parts.len() && part.size() % alignment != 0 {
+        return Err(ConfigError::MisalignedBufferPart {
+          role,
+          index,
+          size: part.size(),
+          alignment,
+        });
+      }
+    }
+
+    Ok(())
+  }
+
   pub(crate) fn as_sys(&self) -> Result<Pin<Box<ConfigGuard>>, ConfigError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!(
+      "vkfft::Config::as_sys",
+      size = ?self.size,
+      precision = ?self.precision,
+      transform = %self.transform,
+      buffer_sizes = ?self.buffer_sizes(),
+    )
+    .entered();
+
+    self.validate()?;
+
     use std::mem::{transmute, zeroed};
 
     unsafe {
       let keep_alive = KeepAlive {
         device: self.device.clone(),
-        buffer: self.buffer.as_ref().map(|b| b.as_buffer().cloned()).flatten(),
-        input_buffer: self.input_buffer.as_ref().map(|b| b.as_buffer().cloned()).flatten(),
-        output_buffer: self.output_buffer.as_ref().map(|b| b.as_buffer().cloned()).flatten(),
-        kernel: self.kernel.as_ref().map(|b| b.as_buffer().cloned()).flatten(),
         command_pool: self.command_pool.clone(),
         queue: self.queue.clone(),
-        temp_buffer: self.temp_buffer.as_ref().map(|b| b.as_buffer().cloned()).flatten()
+        buffers: [
+          &self.buffer,
+          &self.input_buffer,
+          &self.output_buffer,
+          &self.temp_buffer,
+          &self.kernel,
+        ]
+        .iter()
+        .filter_map(|desc| desc.as_ref())
+        .flat_map(BufferDesc::parts)
+        .filter_map(BufferDesc::as_buffer)
+        .cloned()
+        .collect(),
       };
 
       let mut res = Box::pin(ConfigGuard {
-        keep_alive, 
+        keep_alive,
         config: zeroed(),
+        layout: self.layout(),
+        direction: self.direction,
+        convolution_op: self.convolution_op,
+        scale: self.scale(),
         physical_device: self.physical_device.internal_object(),
         device: self.device.internal_object().value() as usize,
         queue: self.queue.internal_object_guard().value() as usize,
         command_pool: self.command_pool.internal_object().value(),
         fence: self.fence.internal_object().value(),
-        buffer_size: self.buffer.as_ref().map(|b| b.size()).unwrap_or(0) as u64,
-        temp_buffer_size: self.temp_buffer.as_ref().map(|b| b.size()).unwrap_or(0) as u64,
-        input_buffer_size: self.input_buffer.as_ref().map(|b| b.size()).unwrap_or(0) as u64,
-        output_buffer_size: self.output_buffer.as_ref().map(|b| b.size()).unwrap_or(0) as u64,
-        kernel_size: self.kernel.as_ref().map(|b| b.size()).unwrap_or(0) as u64,
-        buffer: self
-          .buffer
-          .as_ref()
-          .map(|b| b.as_buffer())
-          .flatten()
-          .map(|b| b.inner().buffer.internal_object().value()),
-        temp_buffer: self
-          .temp_buffer
-          .as_ref()
-          .map(|b| b.as_buffer())
-          .flatten()
-          .map(|b| b.inner().buffer.internal_object().value()),
-        input_buffer: self
-          .input_buffer
-          .as_ref()
-          .map(|b| b.as_buffer())
-          .flatten()
-          .map(|b| b.inner().buffer.internal_object().value()),
-        output_buffer: self
-          .output_buffer
-          .as_ref()
-          .map(|b| b.as_buffer())
-          .flatten()
-          .map(|b| b.inner().buffer.internal_object().value()),
-        kernel: self
-          .kernel
-          .as_ref()
-          .map(|b| b.as_buffer())
-          .flatten()
-          .map(|b| b.inner().buffer.internal_object().value()),
+        buffer: BufferGuard::new(self.buffer.as_ref()),
+        input_buffer: BufferGuard::new(self.input_buffer.as_ref()),
+        output_buffer: BufferGuard::new(self.output_buffer.as_ref()),
+        temp_buffer: BufferGuard::new(self.temp_buffer.as_ref()),
+        kernel: BufferGuard::new(self.kernel.as_ref()),
       });
 
-      res.config.FFTdim = self.fft_dim as u64;
-      res.config.size = self.size.map(u64::from);
+      // Buffers passed at launch may be slices with their own offsets, so VkFFT must read
+      // offsets from the launch parameters. `App::launch` fills in the configured ones.
+      res.config.specifyOffsetsAtLaunch = true.into();
 
       res.config.physicalDevice = transmute(addr_of_mut!(res.physical_device));
       res.config.device = transmute(addr_of_mut!(res.device));
       res.config.queue = transmute(addr_of_mut!(res.queue));
       res.config.commandPool = transmute(addr_of_mut!(res.command_pool));
       res.config.fence = transmute(addr_of_mut!(res.fence));
-      res.config.normalize = self.normalize.into();
 
-      if res.kernel_size != 0 {
-        res.config.kernelNum = 1;
-        res.config.kernelSize = transmute(addr_of_mut!(res.kernel_size));
+      // VkFFT reads the sizes and handles through pointers into the guard's vectors, whose heap
+      // allocations stay in place for as long as the guard lives
+      if !res.kernel.sizes.is_empty() {
+        res.config.kernelNum = res.kernel.sizes.len() as u64;
+        res.config.kernelSize = res.kernel.sizes.as_mut_ptr();
       }
 
-      if let Some(t) = &res.kernel {
-        println!("K: {:#0x}", t);
-        res.config.kernel = transmute(t);
+      if !res.kernel.buffers.is_empty() {
+        res.config.kernel = transmute(res.kernel.buffers.as_mut_ptr());
       }
 
-      if res.buffer_size != 0 {
-        res.config.bufferNum = 1;
-        res.config.bufferSize = transmute(addr_of_mut!(res.buffer_size));
+      res.config.kernelOffset = res.kernel.offset;
+
+      if !res.buffer.sizes.is_empty() {
+        res.config.bufferNum = res.buffer.sizes.len() as u64;
+        res.config.bufferSize = res.buffer.sizes.as_mut_ptr();
       }
 
-      if let Some(t) = &res.buffer {
-        println!("B: {:#0x}", *t);
-        res.config.buffer = transmute(t);
+      if !res.buffer.buffers.is_empty() {
+        res.config.buffer = transmute(res.buffer.buffers.as_mut_ptr());
       }
 
-      if res.temp_buffer_size != 0 {
-        res.config.tempBufferNum = 1;
-        res.config.tempBufferSize = transmute(addr_of_mut!(res.temp_buffer_size));
+      res.config.bufferOffset = res.buffer.offset;
+
+      if !res.temp_buffer.sizes.is_empty() {
+        res.config.tempBufferNum = res.temp_buffer.sizes.len() as u64;
+        res.config.tempBufferSize = res.temp_buffer.sizes.as_mut_ptr();
       }
 
-      if let Some(t) = &res.temp_buffer {
-        println!("T: {:#0x}", *t);
-        res.config.tempBuffer = transmute(t);
+      if !res.temp_buffer.buffers.is_empty() {
+        res.config.tempBuffer = transmute(res.temp_buffer.buffers.as_mut_ptr());
       }
 
-      if res.input_buffer_size != 0 {
-        res.config.inputBufferNum = 1;
-        res.config.inputBufferSize = transmute(addr_of_mut!(res.input_buffer_size));
+      res.config.tempBufferOffset = res.temp_buffer.offset;
+
+      if !res.input_buffer.sizes.is_empty() {
+        res.config.inputBufferNum = res.input_buffer.sizes.len() as u64;
+        res.config.inputBufferSize = res.input_buffer.sizes.as_mut_ptr();
       }
 
-      if let Some(t) = &res.input_buffer {
-        println!("I: {:#0x}", *t);
-        res.config.inputBuffer = transmute(t);
+      if !res.input_buffer.buffers.is_empty() {
+        res.config.inputBuffer = transmute(res.input_buffer.buffers.as_mut_ptr());
       }
 
-      if res.output_buffer_size != 0 {
-        res.config.outputBufferNum = 1;
-        res.config.outputBufferSize = transmute(addr_of_mut!(res.output_buffer_size));
+      res.config.inputBufferOffset = res.input_buffer.offset;
 
+      if !res.output_buffer.sizes.is_empty() {
+        res.config.outputBufferNum = res.output_buffer.sizes.len() as u64;
+        res.config.outputBufferSize = res.output_buffer.sizes.as_mut_ptr();
       }
 
-      if let Some(t) = &res.output_buffer {
-        println!("O: {:#0x}", *t);
-        res.config.outputBuffer = transmute(t);
+      if !res.output_buffer.buffers.is_empty() {
+        res.config.outputBuffer = transmute(res.output_buffer.buffers.as_mut_ptr());
       }
 
-      res.config.performZeropadding[0] = self.zero_padding[0].into();
-      res.config.performZeropadding[1] = self.zero_padding[1].into();
-      res.config.performZeropadding[2] = self.zero_padding[2].into();
+      res.config.outputBufferOffset = res.output_buffer.offset;
+
+      let layout = res.layout;
+      layout.apply(&mut res.config);
+      self.options().apply(&mut res.config);
+
+      #[cfg(feature = "tracing")]
+      for role in BufferRole::ALL.iter() {
+        let guard = res.buffer_guard(*role);
+        tracing::trace!(
+          role = %role,
+          handles = ?guard.buffers,
+          sizes = ?guard.sizes,
+          offset = guard.offset,
+          "configured buffer"
+        );
+      }
 
-      res.config.fft_zeropad_left = self.zeropad_left.map(u64::from);
-      res.config.fft_zeropad_right = self.zeropad_right.map(u64::from);
+      Ok(res)
+    }
+  }
+}
 
-      res.config.kernelConvolution = self.kernel_convolution.into();
-      res.config.performR2C = self.r2c.into();
-      res.config.coordinateFeatures = self.coordinate_features as u64;
-      res.config.disableReorderFourStep = self.disable_reorder_four_step.into();
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-      res.config.symmetricKernel = self.symmetric_kernel.into();
+  /// Extend values of the leading axes to all axes, repeating `fill`
+  fn axes<T: Copy>(values: &[T], fill: T) -> [T; MAX_DIMENSIONS] {
+    let mut res = [fill; MAX_DIMENSIONS];
+    res[..values.len()].copy_from_slice(values);
+    res
+  }
 
-      if let Some(input_formatted) = self.input_formatted {
-        res.config.isInputFormatted = input_formatted.into();
-      }
+  fn layout(size: &[u64]) -> Layout {
+    Layout {
+      size: Shape::new(size).unwrap(),
+      transform: Transform::C2c,
+      precision: Precision::Single,
+      coordinate_features: 1,
+      batch_count: 1,
+      kernel_convolution: false,
+      convolution: false,
+      convolution_mode: ConvolutionMode::Vector,
+      kernel_count: 1,
+      input_formatted: false,
+      output_formatted: false,
+      buffer_layout: None,
+      input_layout: None,
+      output_layout: None,
+      inverse_return_to_input_buffer: false,
+    }
+  }
 
-      if let Some(output_formatted) = self.output_formatted {
-        res.config.isOutputFormatted = output_formatted.into();
-      }
+  /// The VkFFT configuration `builder` describes, without its devices and buffers
+  fn sys(builder: ConfigBuilder) -> vkfft_sys::VkFFTConfiguration {
+    let mut config = unsafe { std::mem::zeroed() };
+    builder.layout().apply(&mut config);
+    builder.options().unwrap().apply(&mut config);
+    config
+  }
 
-      match self.precision {
-        Precision::Double => {
-          res.config.doublePrecision = true.into();
-        }
-        Precision::Half => res.config.halfPrecision = true.into(),
-        Precision::HalfMemory => {
-          res.config.halfPrecisionMemoryOnly = true.into();
+  #[test]
+  fn c2c_buffer_sizes() {
+    let sizes = layout(&[8, 4]).buffer_sizes();
+    assert_eq!(sizes.buffer, 8 * 4 * 2 * 4);
+    assert_eq!(sizes.input_buffer, sizes.buffer);
+    assert_eq!(sizes.temp_buffer, sizes.buffer);
+
+    let double = Layout {
+      precision: Precision::Double,
+      coordinate_features: 2,
+      batch_count: 3,
+      ..layout(&[8, 4])
+    };
+    assert_eq!(double.buffer_sizes().buffer, 8 * 4 * 2 * 8 * 2 * 3);
+  }
 
-          if let Some(false) = self.input_formatted {
-            return Err(ConfigError::InvalidConfig);
-          }
+  #[test]
+  fn r2c_buffer_sizes() {
+    let r2c = Layout {
+      transform: Transform::R2c,
+      coordinate_features: 2,
+      ..layout(&[32, 32])
+    };
+    let sizes = r2c.buffer_sizes();
+    assert_eq!(sizes.buffer, 2 * 2 * (32 / 2 + 1) * 32 * 4);
+    assert_eq!(sizes.input_buffer, sizes.buffer);
 
-          if let Some(false) = self.output_formatted {
-            return Err(ConfigError::InvalidConfig);
-          }
+    let formatted = Layout {
+      input_formatted: true,
+      ..r2c
+    };
+    assert_eq!(formatted.buffer_sizes().input_buffer, 2 * 32 * 32 * 4);
+    assert_eq!(formatted.buffer_sizes().output_buffer, sizes.buffer);
+  }
 
-          res.config.isInputFormatted = true.into();
-          res.config.isOutputFormatted = true.into();
-        }
-        _ => {}
-      }
+  #[test]
+  fn half_memory_buffer_sizes() {
+    let sizes = Layout {
+      transform: Transform::R2c,
+      precision: Precision::HalfMemory,
+      ..layout(&[16])
+    }
+    .buffer_sizes();
+    assert_eq!(sizes.buffer, (16 / 2 + 1) * 2 * 4);
+    assert_eq!(sizes.input_buffer, 16 * 2);
+    assert_eq!(sizes.output_buffer, 16 * 2);
+  }
+
+  #[test]
+  fn element_types() {
+    assert_eq!(ElementType::of::<[f32]>(), Some(ElementType::F32));
+    assert_eq!(ElementType::of::<[[f64; 2]]>(), Some(ElementType::F64));
+    assert_eq!(ElementType::of::<[f16]>(), Some(ElementType::F16));
+    assert_eq!(ElementType::of::<[u8]>(), None);
+
+    assert_eq!(
+      Precision::HalfMemory.element_type(BufferRole::InputBuffer),
+      ElementType::F16
+    );
+    assert_eq!(
+      Precision::HalfMemory.element_type(BufferRole::Buffer),
+      ElementType::F32
+    );
+  }
+
+  #[test]
+  fn layout_validation() {
+    assert!(layout(&[8, 4]).validate().is_ok());
+
+    assert!(matches!(
+      layout(&[]).validate(),
+      Err(ConfigError::InvalidFftDim { fft_dim: 0 })
+    ));
+    assert!(matches!(
+      Shape::new(&[8, 4, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]),
+      Err(ConfigError::TooManyAxes { count: 14, .. })
+    ));
+    assert!(matches!(
+      layout(&[8, 0]).validate(),
+      Err(ConfigError::ZeroSize { axis: 1 })
+    ));
+    assert!(matches!(
+      layout(&[u64::MAX, u64::MAX, u64::MAX]).validate(),
+      Err(ConfigError::SizeOverflow { .. })
+    ));
+    // 2^61 complex values fit, but not in bytes
+    assert!(matches!(
+      layout(&[1 << 31, 1 << 30]).validate(),
+      Err(ConfigError::SizeOverflow {
+        role: BufferRole::Buffer,
+        ..
+      })
+    ));
+
+    let odd_r2c = Layout {
+      transform: Transform::R2c,
+      ..layout(&[9, 4])
+    };
+    assert!(matches!(
+      odd_r2c.validate(),
+      Err(ConfigError::OddR2cSize { size: 9 })
+    ));
+  }
+
+  #[test]
+  fn strided_layouts() {
+    // Rows of 8 complex values padded to 10, two coordinate features
+    let padded = DataLayout {
+      strides: axes(&[1, 10, 40], 40),
+      feature_distance: 40,
+      batch_distance: 80,
+    };
+    let strided = Layout {
+      coordinate_features: 2,
+      buffer_layout: Some(padded),
+      ..layout(&[8, 4])
+    };
+    assert!(strided.validate().is_ok());
+    assert_eq!(strided.buffer_sizes().buffer, (40 + 30 + 7 + 1) * 2 * 4);
+    assert_eq!(strided.buffer_sizes().temp_buffer, 8 * 4 * 2 * 2 * 4);
+
+    let overlapping = Layout {
+      buffer_layout: Some(DataLayout {
+        strides: axes(&[1, 6, 40], 40),
+        ..padded
+      }),
+      ..strided
+    };
+    assert!(matches!(
+      overlapping.validate(),
+      Err(ConfigError::OverlappingLayout { .. })
+    ));
+
+    let batch = Layout {
+      buffer_layout: Some(DataLayout {
+        batch_distance: 100,
+        ..padded
+      }),
+      ..strided
+    };
+    assert!(matches!(
+      batch.validate(),
+      Err(ConfigError::UnsupportedBatchDistance { expected: 80, .. })
+    ));
+
+    let unformatted = Layout {
+      input_layout: Some(padded),
+      ..strided
+    };
+    assert!(matches!(
+      unformatted.validate(),
+      Err(ConfigError::LayoutRequiresFormatted {
+        role: BufferRole::InputBuffer
+      })
+    ));
+  }
+
+  #[test]
+  fn buffer_lists() {
+    let layout = layout(&[8, 4]);
+
+    let split = BufferDesc::from(vec![64u64, 192]);
+    assert_eq!(split.size(), 256);
+    assert_eq!(split.parts().len(), 2);
+    assert!(Config::validate_parts(BufferRole::Buffer, &split, &layout).is_ok());
+
+    let single = BufferDesc::BufferSize(256);
+    assert_eq!(single.parts().len(), 1);
+
+    assert!(matches!(
+      Config::validate_parts(BufferRole::Buffer, &BufferDesc::Buffers(vec![]), &layout),
+      Err(ConfigError::EmptyBufferList { .. })
+    ));
+
+    let nested = BufferDesc::Buffers(vec![BufferDesc::from(vec![256u64])]);
+    assert!(matches!(
+      Config::validate_parts(BufferRole::Buffer, &nested, &layout),
+      Err(ConfigError::InvalidBufferList { .. })
+    ));
+
+    let misaligned = BufferDesc::from(vec![60u64, 196]);
+    assert!(matches!(
+      Config::validate_parts(BufferRole::Buffer, &misaligned, &layout),
+      Err(ConfigError::MisalignedBufferPart {
+        index: 0,
+        alignment: 8,
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn directions() {
+    assert!(Direction::Both.supports(Direction::Forward));
+    assert!(Direction::Both.supports(Direction::Inverse));
+    assert!(Direction::Forward.supports(Direction::Forward));
+    assert!(!Direction::Forward.supports(Direction::Inverse));
+    assert!(!Direction::Inverse.supports(Direction::Forward));
+  }
+
+  #[test]
+  fn omitted_dimensions() {
+    let layout_3d = layout(&[8, 4, 2]);
+    assert!(
+      Config::validate_omit_dimension(axes(&[false, false, true], false), &layout_3d, false)
+        .is_ok()
+    );
+    assert!(matches!(
+      Config::validate_omit_dimension(axes(&[true, true, true], false), &layout_3d, false),
+      Err(ConfigError::AllDimensionsOmitted)
+    ));
+    assert!(matches!(
+      Config::validate_omit_dimension(axes(&[false, true, false], false), &layout_3d, true),
+      Err(ConfigError::OmittedDimensionWithConvolution)
+    ));
+
+    // Axes beyond fft_dim don't count as transformed
+    let layout_2d = layout(&[8, 4]);
+    assert!(matches!(
+      Config::validate_omit_dimension(axes(&[true, true, false], false), &layout_2d, false),
+      Err(ConfigError::AllDimensionsOmitted)
+    ));
+
+    let r2c = Layout {
+      transform: Transform::R2c,
+      ..layout_3d
+    };
+    assert!(matches!(
+      Config::validate_omit_dimension(axes(&[true, false, false], false), &r2c, false),
+      Err(ConfigError::OmittedR2cDimension)
+    ));
+  }
+
+  #[test]
+  fn dct_types() {
+    let transforms = [
+      (Transform::C2c, 0),
+      (Transform::R2c, 0),
+      (Transform::Dct1, 1),
+      (Transform::Dct2, 2),
+      (Transform::Dct3, 3),
+      (Transform::Dct4, 4),
+    ];
+
+    for &(transform, dct_type) in transforms.iter() {
+      assert_eq!(transform.dct_type(), dct_type);
+      assert_eq!(transform.is_dct(), dct_type != 0);
+
+      let config = sys(ConfigBuilder::new().dim(&[16, 8]).transform(transform));
+      assert_eq!(config.performDCT, dct_type);
+      assert_eq!(config.performR2C, (transform == Transform::R2c).into());
+      assert_eq!(config.FFTdim, 2);
+      assert_eq!(config.size, axes(&[16, 8], 1));
+    }
+  }
 
-      if let Some(batch_count) = &self.batch_count {
-        res.config.numberBatches = *batch_count as u64;
+  #[test]
+  fn shader_code_fields() {
+    let config = sys(ConfigBuilder::new().dim(&[16]));
+    assert_eq!(config.keepShaderCode, 0);
+    assert_eq!(config.printMemoryLayout, 0);
+
+    let config = sys(
+      ConfigBuilder::new()
+        .dim(&[16])
+        .keep_shader_code()
+        .print_memory_layout(),
+    );
+    assert_eq!(config.keepShaderCode, 1);
+    assert_eq!(config.printMemoryLayout, 1);
+  }
+
+  #[test]
+  fn dct_layouts() {
+    let dct = Layout {
+      transform: Transform::Dct2,
+      input_formatted: true,
+      ..layout(&[8, 5])
+    };
+    assert!(dct.validate().is_ok());
+
+    let sizes = dct.buffer_sizes();
+    assert_eq!(sizes.buffer, 8 * 5 * 4);
+    assert_eq!(sizes.input_buffer, 8 * 5 * 4);
+
+    let half = Layout {
+      precision: Precision::Half,
+      ..dct
+    };
+    assert!(matches!(
+      half.validate(),
+      Err(ConfigError::UnsupportedTransformPrecision { .. })
+    ));
+
+    let dct1 = Layout {
+      transform: Transform::Dct1,
+      ..layout(&[8, 1])
+    };
+    assert!(matches!(
+      dct1.validate(),
+      Err(ConfigError::InvalidTransformSize {
+        axis: 1,
+        size: 1,
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn tuning_ranges() {
+    assert!(Tuning::default().validate().is_ok());
+
+    let tuning = Tuning {
+      shared_banks: Some(32),
+      coalesced_memory: Some(64),
+      aim_threads: Some(256),
+      max_threads: Some(1024),
+      warp_size: Some(32),
+      register_boost: Some(2),
+      ..Tuning::default()
+    };
+    assert!(tuning.validate().is_ok());
+
+    assert!(matches!(
+      Tuning {
+        coalesced_memory: Some(48),
+        ..tuning
+      }
+      .validate(),
+      Err(ConfigError::InvalidTuning {
+        parameter: "coalesced_memory",
+        value: 48,
+        ..
+      })
+    ));
+    assert!(matches!(
+      Tuning {
+        aim_threads: Some(2048),
+        ..tuning
       }
+      .validate(),
+      Err(ConfigError::InvalidTuning {
+        parameter: "aim_threads",
+        ..
+      })
+    ));
+    assert!(matches!(
+      Tuning {
+        register_boost: Some(3),
+        ..tuning
+      }
+      .validate(),
+      Err(ConfigError::InvalidTuning {
+        parameter: "register_boost",
+        ..
+      })
+    ));
+  }
 
-      Ok(res)
+  #[test]
+  fn kernel_layouts() {
+    let base = Layout {
+      transform: Transform::R2c,
+      coordinate_features: 3,
+      convolution: true,
+      ..layout(&[8, 4])
+    };
+
+    // Complex R2C elements of a single feature and batch
+    let feature = 5 * 4 * 8;
+
+    let vector = base.buffer_sizes();
+    assert_eq!(vector.kernel, 3 * feature);
+    assert_eq!(vector.buffer, 3 * feature);
+
+    let matrix = Layout {
+      convolution_mode: ConvolutionMode::Matrix,
+      kernel_count: 2,
+      ..base
+    }
+    .buffer_sizes();
+    assert_eq!(matrix.kernel, 2 * 9 * feature);
+    assert_eq!(matrix.buffer, 2 * 3 * feature);
+
+    let symmetric = Layout {
+      convolution_mode: ConvolutionMode::SymmetricMatrix,
+      ..base
+    }
+    .buffer_sizes();
+    assert_eq!(symmetric.kernel, 6 * feature);
+
+    // The application transforming the kernels holds them in `buffer`
+    let transform = Layout {
+      convolution: false,
+      kernel_convolution: true,
+      convolution_mode: ConvolutionMode::Matrix,
+      kernel_count: 2,
+      ..base
     }
+    .buffer_sizes();
+    assert_eq!(transform.buffer, matrix.kernel);
+
+    assert!(ConvolutionMode::Scalar.supports(1));
+    assert!(!ConvolutionMode::Scalar.supports(2));
+    assert!(!ConvolutionMode::Matrix.supports(4));
+    assert!(matches!(
+      Layout {
+        kernel_count: 0,
+        ..base
+      }
+      .validate(),
+      Err(ConfigError::ZeroKernelCount)
+    ));
+  }
+
+  #[test]
+  fn scalar_indices() {
+    let c2c = layout(&[8, 4]);
+    assert_eq!(
+      c2c.scalar_index(BufferRole::Buffer, axes(&[3, 2], 0)),
+      2 * (3 + 2 * 8)
+    );
+
+    // In-place R2C rows are padded to 5 complex values
+    let r2c = Layout {
+      transform: Transform::R2c,
+      ..c2c
+    };
+    assert_eq!(
+      r2c.scalar_index(BufferRole::Buffer, axes(&[3, 2], 0)),
+      3 + 2 * 2 * 5
+    );
+
+    let formatted = Layout {
+      output_formatted: true,
+      ..r2c
+    };
+    assert_eq!(
+      formatted.scalar_index(BufferRole::OutputBuffer, axes(&[3, 2], 0)),
+      3 + 2 * 8
+    );
+  }
+
+  #[test]
+  fn shapes() {
+    let shape = Shape::new(&[8, 4]).unwrap();
+    assert_eq!(shape.len(), 2);
+    assert_eq!(&shape[..], &[8, 4]);
+    assert_eq!(shape.padded()[..2], [8, 4]);
+    assert!(shape.padded()[2..].iter().all(|&len| len == 1));
+    assert_eq!(Shape::default().as_slice(), &[1]);
+
+    let too_many = vec![2; MAX_DIMENSIONS + 1];
+    assert!(matches!(
+      ConfigBuilder::new().dim(&too_many).build(),
+      Err(BuildError::Config(ConfigError::TooManyAxes {
+        setting: "dim",
+        ..
+      }))
+    ));
+
+    // Invalid settings are reported before missing devices, and only the first one is kept
+    assert!(matches!(
+      ConfigBuilder::new()
+        .dim(&[8, 4])
+        .zeropad_left(&too_many)
+        .zero_padding(&[true; MAX_DIMENSIONS + 1])
+        .build(),
+      Err(BuildError::Config(ConfigError::TooManyAxes {
+        setting: "zeropad_left",
+        ..
+      }))
+    ));
+
+    let builder = ConfigBuilder::new().dim(&[8, 4]).zeropad_right(&[2, 3]);
+    assert_eq!(builder.zeropad_right[..2], [2, 3]);
+    assert!(matches!(builder.build(), Err(BuildError::NoPhysicalDevice)));
+  }
+
+  #[test]
+  fn typed_configure() {
+    let builder = TypedConfigBuilder::<f32>::new().configure(|builder| builder.dim(&[8, 4]));
+    assert!(matches!(builder.build(), Err(BuildError::NoPhysicalDevice)));
+
+    let builder = TypedConfigBuilder::<f32>::new()
+      .configure(|builder| builder.dim(&[8, 4]).precision(Precision::Double));
+    assert!(matches!(
+      builder.build(),
+      Err(BuildError::Config(ConfigError::TypedPrecisionChanged {
+        expected: Precision::Single,
+        found: Precision::Double,
+      }))
+    ));
+
+    // Sizes carry no element type to get wrong
+    let builder =
+      TypedConfigBuilder::<f32>::new().configure(|builder| builder.dim(&[8, 4]).temp_buffer(256));
+    assert!(matches!(builder.build(), Err(BuildError::NoPhysicalDevice)));
+  }
+
+  #[test]
+  fn large_sizes() {
+    // A 2048^3 volume of complex values is 64 GiB
+    let volume = layout(&[2048, 2048, 2048]);
+    assert!(volume.validate().is_ok());
+    assert_eq!(volume.buffer_sizes().buffer, 2048 * 2048 * 2048 * 8);
+    assert!(volume.requires_64_bit_indexing());
+
+    assert!(!layout(&[1024, 1024, 1024]).requires_64_bit_indexing());
+
+    let overflowing = layout(&[1 << 40, 1 << 40]);
+    assert_eq!(overflowing.buffer_sizes().buffer, u64::MAX);
+    assert!(overflowing.requires_64_bit_indexing());
+
+    let strided = DataLayout {
+      strides: axes(&[1, u64::MAX / 2], 0),
+      feature_distance: u64::MAX,
+      batch_distance: u64::MAX,
+    };
+    assert_eq!(strided.span(axes(&[4, 4], 1), 1, 1), None);
+    assert_eq!(
+      DataLayout::packed(axes(&[4, 4], 1), 1).span(axes(&[4, 4], 1), 1, 1),
+      Some(16)
+    );
+  }
+
+  #[test]
+  fn convolution_ops() {
+    let applied = |op: ConvolutionOp| {
+      let mut config: vkfft_sys::VkFFTConfiguration = unsafe { std::mem::zeroed() };
+      op.apply(&mut config);
+      (
+        config.conjugateConvolution,
+        config.crossPowerSpectrumNormalization,
+      )
+    };
+
+    assert_eq!(applied(ConvolutionOp::Convolution), (0, 0));
+    // The kernel is conjugated, not the input
+    assert_eq!(applied(ConvolutionOp::CrossCorrelation), (2, 0));
+    assert_eq!(applied(ConvolutionOp::PhaseCorrelation), (2, 1));
+  }
+
+  #[test]
+  fn cross_correlation_fields() {
+    let config = sys(
+      ConfigBuilder::new()
+        .dim(&[16, 8])
+        .cross_correlation()
+        .kernel_count(2),
+    );
+    assert_eq!(config.performConvolution, 1);
+    assert_eq!(config.numberKernels, 2);
+    assert_eq!(config.conjugateConvolution, 2);
+    assert_eq!(config.crossPowerSpectrumNormalization, 0);
+
+    let config = sys(ConfigBuilder::new().dim(&[16, 8]).convolution());
+    assert_eq!(config.performConvolution, 1);
+    assert_eq!(config.conjugateConvolution, 0);
+  }
+
+  #[test]
+  fn phase_correlation_fields() {
+    let config = sys(
+      ConfigBuilder::new()
+        .dim(&[16, 8])
+        .r2c()
+        .phase_correlation()
+        .normalize(),
+    );
+    assert_eq!(config.performConvolution, 1);
+    assert_eq!(config.performR2C, 1);
+    assert_eq!(config.conjugateConvolution, 2);
+    assert_eq!(config.crossPowerSpectrumNormalization, 1);
+    assert_eq!(config.normalize, 1);
+  }
+
+  #[test]
+  fn normalization_scales() {
+    assert_eq!(Normalization::default(), Normalization::None);
+    assert_eq!(Normalization::None.scale(64), None);
+    // VkFFT scales the inverse itself
+    assert_eq!(Normalization::Inverse.scale(64), None);
+    assert_eq!(Normalization::Orthonormal.scale(64), Some(0.125));
+    assert_eq!(Normalization::Orthonormal.scale(1), Some(1.0));
+    assert_eq!(Normalization::Custom(2.5).scale(64), Some(2.5));
+
+    // VkFFT only scales the inverse itself
+    let inverse = sys(ConfigBuilder::new().dim(&[16]).normalize());
+    assert_eq!(inverse.normalize, 1);
+    let orthonormal = sys(
+      ConfigBuilder::new()
+        .dim(&[16])
+        .normalization(Normalization::Orthonormal),
+    );
+    assert_eq!(orthonormal.normalize, 0);
+  }
+
+  #[test]
+  fn unsupported_normalizations() {
+    let dct = Layout {
+      transform: Transform::Dct2,
+      ..layout(&[16])
+    };
+    assert!(matches!(
+      Normalization::Orthonormal.validate(&dct),
+      Err(ConfigError::UnsupportedNormalization { .. })
+    ));
+    assert!(Normalization::Inverse.validate(&dct).is_ok());
+    assert!(Normalization::Custom(2.0).validate(&dct).is_ok());
+
+    let convolution = Layout {
+      convolution: true,
+      ..layout(&[16])
+    };
+    assert!(matches!(
+      Normalization::Orthonormal.validate(&convolution),
+      Err(ConfigError::UnsupportedNormalization { .. })
+    ));
+
+    let half = Layout {
+      precision: Precision::Half,
+      ..layout(&[16])
+    };
+    assert!(matches!(
+      Normalization::Custom(2.0).validate(&half),
+      Err(ConfigError::UnsupportedNormalizationPrecision { .. })
+    ));
+    assert!(Normalization::Inverse.validate(&half).is_ok());
+  }
+
+  #[test]
+  fn result_roles() {
+    let r2c = Layout {
+      transform: Transform::R2c,
+      input_formatted: true,
+      ..layout(&[16])
+    };
+    assert_eq!(r2c.result_role(Direction::Forward), BufferRole::Buffer);
+    assert_eq!(r2c.result_role(Direction::Inverse), BufferRole::Buffer);
+
+    let returning = Layout {
+      inverse_return_to_input_buffer: true,
+      ..r2c
+    };
+    assert_eq!(
+      returning.result_role(Direction::Forward),
+      BufferRole::Buffer
+    );
+    assert_eq!(
+      returning.result_role(Direction::Inverse),
+      BufferRole::InputBuffer
+    );
+
+    let output = Layout {
+      output_formatted: true,
+      ..r2c
+    };
+    assert_eq!(
+      output.result_role(Direction::Forward),
+      BufferRole::OutputBuffer
+    );
+    assert_eq!(output.result_role(Direction::Inverse), BufferRole::Buffer);
+  }
+
+  #[test]
+  fn inverse_return_fields() {
+    let config = sys(ConfigBuilder::new().dim(&[16]).r2c());
+    assert_eq!(config.inverseReturnToInputBuffer, 0);
+
+    // Returning to the input buffer implies an unpadded input buffer
+    let config = sys(
+      ConfigBuilder::new()
+        .dim(&[16])
+        .r2c()
+        .inverse_return_to_input_buffer(),
+    );
+    assert_eq!(config.inverseReturnToInputBuffer, 1);
+    assert_eq!(config.isInputFormatted, 1);
+  }
+
+  #[test]
+  fn pruned_zero_blocks() {
+    let size = Shape::new(&[16, 8]).unwrap();
+
+    // Non-zero input at the start of the axis leaves the end to skip
+    let blocks = PrunedFft::nonzero_input()
+      .keep(0, 0, 5)
+      .zero_blocks(&size)
+      .unwrap();
+    assert_eq!(blocks[0], Some((5, 16)));
+    assert_eq!(blocks[1], None);
+
+    // Low frequencies wrap around the end, leaving the high ones in the middle to skip
+    let low = PrunedFft::needed_output().keep(0, 13, 7).keep(1, 6, 5);
+    assert!(low.is_frequency_domain());
+    let blocks = low.zero_blocks(&size).unwrap();
+    assert_eq!(blocks[0], Some((4, 13)));
+    assert_eq!(blocks[1], Some((3, 6)));
+
+    // Keeping a whole axis skips nothing, and a later `keep` of an axis replaces the earlier one
+    let whole = PrunedFft::nonzero_input().keep(1, 2, 3).keep(1, 3, 8);
+    assert_eq!(whole.zero_blocks(&size).unwrap()[1], None);
+
+    assert!(matches!(
+      PrunedFft::nonzero_input().keep(0, 4, 8).zero_blocks(&size),
+      Err(ConfigError::InvalidPruning {
+        axis: 0,
+        start: 4,
+        len: 8,
+        size: 16
+      })
+    ));
+    assert!(matches!(
+      PrunedFft::nonzero_input().keep(0, 0, 0).zero_blocks(&size),
+      Err(ConfigError::InvalidPruning { .. })
+    ));
+    assert!(matches!(
+      PrunedFft::nonzero_input().keep(2, 0, 1).zero_blocks(&size),
+      Err(ConfigError::PrunedAxisOutOfRange {
+        axis: 2,
+        fft_dim: 2
+      })
+    ));
+
+    // Kept values ending at the end of the axis skip a block from its start
+    assert_eq!(
+      PrunedFft::nonzero_input()
+        .keep(0, 11, 5)
+        .zero_blocks(&size)
+        .unwrap()[0],
+      Some((0, 11))
+    );
+    // VkFFT's zero padding is one contiguous block, which can't wrap around the end
+    assert!(matches!(
+      PrunedFft::nonzero_input().keep(0, 2, 5).zero_blocks(&size),
+      Err(ConfigError::InvalidPruning {
+        axis: 0,
+        start: 2,
+        len: 5,
+        size: 16
+      })
+    ));
+    assert!(matches!(
+      PrunedFft::nonzero_input().keep(1, 8, 1).zero_blocks(&size),
+      Err(ConfigError::InvalidPruning { axis: 1, .. })
+    ));
+    assert!(matches!(
+      PrunedFft::nonzero_input().keep(1, 0, 9).zero_blocks(&size),
+      Err(ConfigError::InvalidPruning { axis: 1, .. })
+    ));
+
+    // The zero blocks become VkFFT's zero padding of each axis
+    let config = sys(ConfigBuilder::new().dim(&[16, 8]).pruned(low));
+    assert_eq!(config.performZeropadding, axes(&[1, 1], 0));
+    assert_eq!(config.fft_zeropad_left, axes(&[4, 3], 0));
+    assert_eq!(config.fft_zeropad_right, axes(&[13, 6], 0));
+    assert_eq!(config.frequencyZeroPadding, 1);
+
+    let config = sys(
+      ConfigBuilder::new()
+        .dim(&[16, 8])
+        .pruned(PrunedFft::nonzero_input().keep(0, 0, 5)),
+    );
+    assert_eq!(config.performZeropadding, axes(&[1, 0], 0));
+    assert_eq!(config.fft_zeropad_left, axes(&[5, 0], 0));
+    assert_eq!(config.fft_zeropad_right, axes(&[16, 0], 0));
+    assert_eq!(config.frequencyZeroPadding, 0);
+
+    // The builder resolves the description before looking for devices
+    assert!(matches!(
+      ConfigBuilder::new()
+        .dim(&[16, 8])
+        .pruned(PrunedFft::needed_output().keep(1, 2, 3))
+        .build(),
+      Err(BuildError::Config(ConfigError::InvalidPruning {
+        axis: 1,
+        ..
+      }))
+    ));
   }
 }
@@ -0,0 +1,165 @@
+use derive_more::{Display, Error};
+
+use crate::config::MAX_DIMENSIONS;
+
+#[derive(Display, Debug, Error)]
+pub enum ShiftError {
+  #[display(fmt = "the application doesn't compute a phase correlation")]
+  NotPhaseCorrelation,
+  #[display(
+    fmt = "surface has {} values but the layout requires {}",
+    len,
+    required
+  )]
+  SurfaceTooSmall { len: usize, required: usize },
+}
+
+/// Shift of the input relative to the kernel found by phase correlation. Shifts past half of an
+/// axis wrap around to negative values, and axes of size 1 have no shift.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shift {
+  /// Position of the correlation peak
+  pub integer: [i64; MAX_DIMENSIONS],
+  /// Position of the peak refined by fitting a parabola through it and its neighbours
+  pub subpixel: [f64; MAX_DIMENSIONS],
+}
+
+/// Locate the peak of a circular correlation surface with the given extent
+pub(crate) fn find_shift<F>(extent: [u64; MAX_DIMENSIONS], value: F) -> Shift
+where
+  F: Fn([u64; MAX_DIMENSIONS]) -> f64,
+{
+  let mut peak = [0; MAX_DIMENSIONS];
+  let mut max = f64::NEG_INFINITY;
+
+  // Visit every position, the first axis varying fastest
+  let mut pos = [0; MAX_DIMENSIONS];
+  loop {
+    let v = value(pos);
+    if v > max {
+      max = v;
+      peak = pos;
+    }
+
+    match (0..MAX_DIMENSIONS).find(|&axis| pos[axis] + 1 < extent[axis]) {
+      Some(axis) => {
+        pos[axis] += 1;
+        pos[..axis].iter_mut().for_each(|p| *p = 0);
+      }
+      None => break,
+    }
+  }
+
+  let mut res = Shift {
+    integer: [0; MAX_DIMENSIONS],
+    subpixel: [0.0; MAX_DIMENSIONS],
+  };
+
+  for axis in 0..MAX_DIMENSIONS {
+    let len = extent[axis] as i64;
+    if len == 1 {
+      continue;
+    }
+
+    let neighbour = |offset: i64| {
+      let mut pos = peak;
+      pos[axis] = (peak[axis] as i64 + offset).rem_euclid(len) as u64;
+      value(pos)
+    };
+
+    let (left, right) = (neighbour(-1), neighbour(1));
+    let curvature = left - 2.0 * max + right;
+    let delta = if curvature != 0.0 {
+      (0.5 * (left - right) / curvature).clamp(-0.5, 0.5)
+    } else {
+      0.0
+    };
+
+    let integer = peak[axis] as i64;
+    let integer = if integer > len / 2 {
+      integer - len
+    } else {
+      integer
+    };
+
+    res.integer[axis] = integer;
+    res.subpixel[axis] = integer as f64 + delta;
+  }
+
+  res
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::Shape;
+
+  fn extent(dims: &[u64]) -> [u64; MAX_DIMENSIONS] {
+    Shape::new(dims).unwrap().padded()
+  }
+
+  /// A surface of zeros with a single peak at `peak`, padded with zeros for the remaining axes
+  fn delta(peak: &[u64]) -> impl Fn([u64; MAX_DIMENSIONS]) -> f64 + '_ {
+    move |pos| {
+      let at_peak = pos
+        .iter()
+        .enumerate()
+        .all(|(axis, &p)| p == peak.get(axis).copied().unwrap_or(0));
+      if at_peak {
+        1.0
+      } else {
+        0.0
+      }
+    }
+  }
+
+  #[test]
+  fn peaks() {
+    // A peak between x = 3 and x = 4, closer to 4, at y = 6 which wraps around to -2
+    let surface = |pos: [u64; MAX_DIMENSIONS]| {
+      let dx = pos[0] as f64 - 3.7;
+      let dy = pos[1] as f64 - 6.0;
+      1.0 - 0.1 * (dx * dx + dy * dy)
+    };
+
+    let shift = find_shift(extent(&[16, 8]), surface);
+    assert_eq!(shift.integer[..2], [4, -2]);
+    assert!(shift.integer[2..].iter().all(|&v| v == 0));
+    assert!((shift.subpixel[0] - 3.7).abs() < 1e-9);
+    assert!((shift.subpixel[1] + 2.0).abs() < 1e-9);
+    assert!(shift.subpixel[2..].iter().all(|&v| v == 0.0));
+  }
+
+  #[test]
+  fn wrapped_peaks() {
+    // A peak at x = -0.4, whose left neighbour is the last value of the axis
+    let surface = |pos: [u64; MAX_DIMENSIONS]| {
+      let dx = (pos[0] as f64 + 0.4).rem_euclid(16.0);
+      let dx = dx.min(16.0 - dx);
+      1.0 - 0.1 * dx * dx
+    };
+
+    let shift = find_shift(extent(&[16]), surface);
+    assert_eq!(shift.integer, [0; MAX_DIMENSIONS]);
+    assert!((shift.subpixel[0] + 0.4).abs() < 1e-9);
+
+    // Half of an axis is still a positive shift, past it wraps around
+    assert_eq!(find_shift(extent(&[16]), delta(&[8])).integer[0], 8);
+    assert_eq!(find_shift(extent(&[16]), delta(&[9])).integer[0], -7);
+  }
+
+  #[test]
+  fn volume_peaks() {
+    let shift = find_shift(extent(&[4, 6, 5]), delta(&[1, 4, 3]));
+    assert_eq!(shift.integer[..3], [1, -2, -2]);
+    assert_eq!(shift.subpixel[..3], [1.0, -2.0, -2.0]);
+  }
+
+  #[test]
+  fn flat_surface() {
+    // Without a peak, the first position is taken and there is nothing to refine
+    let shift = find_shift(extent(&[8, 8]), |_| 0.5);
+    assert_eq!(shift.integer, [0; MAX_DIMENSIONS]);
+    assert_eq!(shift.subpixel, [0.0; MAX_DIMENSIONS]);
+  }
+}
@@ -1,11 +1,166 @@
-use std::convert::{TryFrom, TryInto};
+use std::{
+  convert::{TryFrom, TryInto},
+  fmt,
+};
 
 use derive_more::{Display, Error};
+use vulkano::{
+  buffer::cpu_access::{ReadLockError, WriteLockError},
+  command_buffer::submit::SubmitCommandBufferError,
+  device::DeviceCreationError,
+  instance::InstanceCreationError,
+  memory::DeviceMemoryAllocError,
+  sync::FenceWaitError,
+  OomError,
+};
 
-use crate::{app::LaunchError, config::ConfigError};
+use crate::{
+  app::{self, LaunchError},
+  config::{self, ConfigError, Direction, Precision, Shape, Transform},
+  correlation::ShiftError,
+  plan::PlanError,
+};
 
+/// Coarse category of an [`Error`], for callers that only need to decide how to react to it
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+  /// The configuration or its use is invalid, or asks for something VkFFT doesn't support
+  #[display(fmt = "configuration")]
+  Configuration,
+  /// The host or the device ran out of memory or of some other resource
+  #[display(fmt = "resource exhaustion")]
+  ResourceExhaustion,
+  /// Generating, compiling or linking a shader failed
+  #[display(fmt = "shader compilation")]
+  ShaderCompilation,
+  /// The Vulkan implementation failed, e.g. a lost device or a failed submission
+  #[display(fmt = "driver")]
+  Driver,
+  /// The system lacks what the application needs, e.g. the Vulkan library, a device, or a queue
+  /// or an extension it supports
+  #[display(fmt = "environment")]
+  Environment,
+}
+
+/// Step of an application's lifetime during which VkFFT or Vulkan failed.
+///
+/// VkFFT doesn't report which axis an initialization or a dispatch failed on, so
+/// [`ConfigSummary`] lists the sizes of all of them.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+  /// Planning the transform and compiling its shaders
+  #[display(fmt = "initialize")]
+  Init,
+  /// Creating an application from a saved plan
+  #[display(fmt = "load the plan of")]
+  LoadPlan,
+  /// Recording a transform into a command buffer
+  #[display(fmt = "append the {} transform of", direction)]
+  Append { direction: Direction },
+}
+
+/// The parts of a configuration that identify a failing application in an error message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigSummary {
+  pub size: Shape,
+  pub transform: Transform,
+  pub precision: Precision,
+  pub direction: Direction,
+  pub coordinate_features: u32,
+  pub batch_count: u32,
+}
+
+impl fmt::Display for ConfigSummary {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{:?} {} application ({:?} precision, {}, {} coordinate features, {} batches)",
+      self.size,
+      self.transform,
+      self.precision,
+      self.direction,
+      self.coordinate_features,
+      self.batch_count
+    )
+  }
+}
+
+/// Errors of the vulkano calls around an application, e.g. in the helpers that create the
+/// context, record the command buffer and read back the results
+#[derive(Display, Debug, Error)]
+pub enum VulkanError {
+  #[display(fmt = "no Vulkan physical device is available")]
+  NoPhysicalDevice,
+  #[display(fmt = "no queue family supports the required operations")]
+  NoQueueFamily,
+  #[display(fmt = "the command pool returned no command buffer")]
+  NoCommandBuffer,
+  /// A Vulkan call of the pass applying the normalizations VkFFT doesn't implement failed
+  #[display(fmt = "{} failed with VkResult {} in the scaling pass", call, result)]
+  ScalePass {
+    call: &'static str,
+    result: i32,
+  },
+  Oom(OomError),
+  InstanceCreation(InstanceCreationError),
+  DeviceCreation(DeviceCreationError),
+  DeviceMemoryAlloc(DeviceMemoryAllocError),
+  SubmitCommandBuffer(SubmitCommandBufferError),
+  FenceWait(FenceWaitError),
+  ReadLock(ReadLockError),
+  WriteLock(WriteLockError),
+}
+
+impl VulkanError {
+  pub fn kind(&self) -> ErrorKind {
+    match self {
+      Self::Oom(_)
+      | Self::InstanceCreation(InstanceCreationError::OomError(_))
+      | Self::DeviceCreation(DeviceCreationError::OutOfHostMemory)
+      | Self::DeviceCreation(DeviceCreationError::OutOfDeviceMemory)
+      | Self::DeviceCreation(DeviceCreationError::TooManyObjects)
+      | Self::DeviceMemoryAlloc(DeviceMemoryAllocError::OomError(_))
+      | Self::DeviceMemoryAlloc(DeviceMemoryAllocError::TooManyObjects)
+      | Self::SubmitCommandBuffer(SubmitCommandBufferError::OomError(_))
+      | Self::FenceWait(FenceWaitError::OomError(_)) => ErrorKind::ResourceExhaustion,
+      Self::ScalePass { result, .. }
+        if matches!(
+          *result as vk_sys::Result,
+          vk_sys::ERROR_OUT_OF_HOST_MEMORY
+            | vk_sys::ERROR_OUT_OF_DEVICE_MEMORY
+            | vk_sys::ERROR_TOO_MANY_OBJECTS
+            | vk_sys::ERROR_OUT_OF_POOL_MEMORY_KHR
+        ) =>
+      {
+        ErrorKind::ResourceExhaustion
+      }
+      Self::NoPhysicalDevice
+      | Self::NoQueueFamily
+      | Self::InstanceCreation(InstanceCreationError::LoadingError(_))
+      | Self::InstanceCreation(InstanceCreationError::LayerNotPresent)
+      | Self::InstanceCreation(InstanceCreationError::ExtensionNotPresent)
+      | Self::InstanceCreation(InstanceCreationError::IncompatibleDriver)
+      | Self::DeviceCreation(DeviceCreationError::FeatureNotPresent)
+      | Self::DeviceCreation(DeviceCreationError::ExtensionNotPresent) => ErrorKind::Environment,
+      Self::DeviceCreation(DeviceCreationError::TooManyQueuesForFamily)
+      | Self::DeviceCreation(DeviceCreationError::PriorityOutOfRange)
+      | Self::ReadLock(_)
+      | Self::WriteLock(_) => ErrorKind::Configuration,
+      _ => ErrorKind::Driver,
+    }
+  }
+}
+
+/// Any error of this crate. VkFFT result codes map to the unit variants; failures of an
+/// application are wrapped in [`Error::Failed`] with the operation and configuration they
+/// happened in.
 #[derive(Display, Debug, Error)]
 pub enum Error {
+  MallocFailed,
+  InsufficientCodeBuffer,
+  InsufficientTempBuffer,
+  PlanNotInitialized,
+  NullTempPassed,
   InvalidPhysicalDevice,
   InvalidDevice,
   InvalidQueue,
@@ -15,6 +170,7 @@ pub enum Error {
   OnlyInverseFftInitialized,
   InvalidContext,
   InvalidPlatform,
+  EnabledSaveApplicationToString,
   EmptyFftDim,
   EmptySize,
   EmptyBufferSize,
@@ -27,9 +183,12 @@ pub enum Error {
   EmptyOutputBuffer,
   EmptyKernelSize,
   EmptyKernel,
+  EmptyApplicationString,
   UnsupportedRadix,
   UnsupportedFftLength,
   UnsupportedFftLengthR2C,
+  UnsupportedFftLengthDct,
+  UnsupportedFftOmit,
   FailedToAllocate,
   FailedToMapMemory,
   FailedToAllocateCommandBuffers,
@@ -81,7 +240,101 @@ pub enum Error {
   FailedToReleaseCommandQueue,
   FailedToEnumerateDevices,
   Config(ConfigError),
+  Build(config::BuildError),
+  LaunchParams(app::BuildError),
   Launch(LaunchError),
+  Plan(PlanError),
+  Shift(ShiftError),
+  /// A result code this version of the crate doesn't know, e.g. from a newer VkFFT
+  #[display(fmt = "unknown VkFFT result code {}", _0)]
+  Unknown(#[error(not(source))] vkfft_sys::VkFFTResult),
+  Vulkan(VulkanError),
+  #[display(fmt = "failed to {} the {}: {}", operation, config, source)]
+  Failed {
+    operation: Operation,
+    config: ConfigSummary,
+    source: Box<Error>,
+  },
+}
+
+impl Error {
+  pub fn kind(&self) -> ErrorKind {
+    match self {
+      Self::Failed { source, .. } => source.kind(),
+      Self::Vulkan(e) => e.kind(),
+      Self::MallocFailed
+      | Self::InsufficientCodeBuffer
+      | Self::InsufficientTempBuffer
+      | Self::FailedToAllocate
+      | Self::FailedToAllocateCommandBuffers
+      | Self::FailedToAllocateDescriptorSets
+      | Self::FailedToAllocateMemory => ErrorKind::ResourceExhaustion,
+      Self::FailedShaderPreprocess
+      | Self::FailedShaderParse
+      | Self::FailedShaderLink
+      | Self::FailedSpirvGenerate
+      | Self::FailedToCreateShaderModule
+      | Self::FailedToCreateProgram
+      | Self::FailedToCompileProgram
+      | Self::FailedToGetCodeSize
+      | Self::FailedToGetCode
+      | Self::FailedToAddNameExpression
+      | Self::FailedToCreatePipeline => ErrorKind::ShaderCompilation,
+      Self::PlanNotInitialized
+      | Self::NullTempPassed
+      | Self::InvalidPhysicalDevice
+      | Self::InvalidDevice
+      | Self::InvalidQueue
+      | Self::InvalidCommandPool
+      | Self::InvalidFence
+      | Self::OnlyForwardFftInitialized
+      | Self::OnlyInverseFftInitialized
+      | Self::InvalidContext
+      | Self::InvalidPlatform
+      | Self::EnabledSaveApplicationToString
+      | Self::EmptyFftDim
+      | Self::EmptySize
+      | Self::EmptyBufferSize
+      | Self::EmptyBuffer
+      | Self::EmptyTempBufferSize
+      | Self::EmptyTempBuffer
+      | Self::EmptyInputBufferSize
+      | Self::EmptyInputBuffer
+      | Self::EmptyOutputBufferSize
+      | Self::EmptyOutputBuffer
+      | Self::EmptyKernelSize
+      | Self::EmptyKernel
+      | Self::EmptyApplicationString
+      | Self::UnsupportedRadix
+      | Self::UnsupportedFftLength
+      | Self::UnsupportedFftLengthR2C
+      | Self::UnsupportedFftLengthDct
+      | Self::UnsupportedFftOmit
+      | Self::Config(_)
+      | Self::Build(_)
+      | Self::LaunchParams(_)
+      | Self::Launch(_)
+      | Self::Plan(_)
+      | Self::Shift(_) => ErrorKind::Configuration,
+      _ => ErrorKind::Driver,
+    }
+  }
+
+  /// The error without the operation and configuration it happened in
+  pub fn inner(&self) -> &Error {
+    match self {
+      Self::Failed { source, .. } => source.inner(),
+      _ => self,
+    }
+  }
+
+  pub(crate) fn during(self, operation: Operation, config: ConfigSummary) -> Self {
+    Self::Failed {
+      operation,
+      config,
+      source: Box::new(self),
+    }
+  }
 }
 
 impl TryFrom<vkfft_sys::VkFFTResult> for Error {
@@ -92,6 +345,11 @@ impl TryFrom<vkfft_sys::VkFFTResult> for Error {
     use vkfft_sys::*;
 
     match value {
+      VkFFTResult_VKFFT_ERROR_MALLOC_FAILED => Ok(Self::MallocFailed),
+      VkFFTResult_VKFFT_ERROR_INSUFFICIENT_CODE_BUFFER => Ok(Self::InsufficientCodeBuffer),
+      VkFFTResult_VKFFT_ERROR_INSUFFICIENT_TEMP_BUFFER => Ok(Self::InsufficientTempBuffer),
+      VkFFTResult_VKFFT_ERROR_PLAN_NOT_INITIALIZED => Ok(Self::PlanNotInitialized),
+      VkFFTResult_VKFFT_ERROR_NULL_TEMP_PASSED => Ok(Self::NullTempPassed),
       VkFFTResult_VKFFT_ERROR_INVALID_PHYSICAL_DEVICE => Ok(Self::InvalidPhysicalDevice),
       VkFFTResult_VKFFT_ERROR_INVALID_DEVICE => Ok(Self::InvalidDevice),
       VkFFTResult_VKFFT_ERROR_INVALID_QUEUE => Ok(Self::InvalidQueue),
@@ -101,6 +359,9 @@ impl TryFrom<vkfft_sys::VkFFTResult> for Error {
       VkFFTResult_VKFFT_ERROR_ONLY_INVERSE_FFT_INITIALIZED => Ok(Self::OnlyInverseFftInitialized),
       VkFFTResult_VKFFT_ERROR_INVALID_CONTEXT => Ok(Self::InvalidContext),
       VkFFTResult_VKFFT_ERROR_INVALID_PLATFORM => Ok(Self::InvalidPlatform),
+      VkFFTResult_VKFFT_ERROR_ENABLED_saveApplicationToString => {
+        Ok(Self::EnabledSaveApplicationToString)
+      }
       VkFFTResult_VKFFT_ERROR_EMPTY_FFTdim => Ok(Self::EmptyFftDim),
       VkFFTResult_VKFFT_ERROR_EMPTY_size => Ok(Self::EmptySize),
       VkFFTResult_VKFFT_ERROR_EMPTY_bufferSize => Ok(Self::EmptyBufferSize),
@@ -113,9 +374,12 @@ impl TryFrom<vkfft_sys::VkFFTResult> for Error {
       VkFFTResult_VKFFT_ERROR_EMPTY_outputBuffer => Ok(Self::EmptyOutputBuffer),
       VkFFTResult_VKFFT_ERROR_EMPTY_kernelSize => Ok(Self::EmptyKernelSize),
       VkFFTResult_VKFFT_ERROR_EMPTY_kernel => Ok(Self::EmptyKernel),
+      VkFFTResult_VKFFT_ERROR_EMPTY_applicationString => Ok(Self::EmptyApplicationString),
       VkFFTResult_VKFFT_ERROR_UNSUPPORTED_RADIX => Ok(Self::UnsupportedRadix),
       VkFFTResult_VKFFT_ERROR_UNSUPPORTED_FFT_LENGTH => Ok(Self::UnsupportedFftLength),
       VkFFTResult_VKFFT_ERROR_UNSUPPORTED_FFT_LENGTH_R2C => Ok(Self::UnsupportedFftLengthR2C),
+      VkFFTResult_VKFFT_ERROR_UNSUPPORTED_FFT_LENGTH_DCT => Ok(Self::UnsupportedFftLengthDct),
+      VkFFTResult_VKFFT_ERROR_UNSUPPORTED_FFT_OMIT => Ok(Self::UnsupportedFftOmit),
       VkFFTResult_VKFFT_ERROR_FAILED_TO_ALLOCATE => Ok(Self::FailedToAllocate),
       VkFFTResult_VKFFT_ERROR_FAILED_TO_MAP_MEMORY => Ok(Self::FailedToMapMemory),
       VkFFTResult_VKFFT_ERROR_FAILED_TO_ALLOCATE_COMMAND_BUFFERS => {
@@ -201,17 +465,206 @@ impl From<ConfigError> for Error {
   }
 }
 
+impl From<config::BuildError> for Error {
+  /// Invalid configurations are reported as [`Error::Config`] whether they were found while
+  /// building the configuration or later
+  fn from(e: config::BuildError) -> Self {
+    match e {
+      config::BuildError::Config(e) => Self::Config(e),
+      e => Self::Build(e),
+    }
+  }
+}
+
+impl From<app::BuildError> for Error {
+  fn from(e: app::BuildError) -> Self {
+    Self::LaunchParams(e)
+  }
+}
+
 impl From<LaunchError> for Error {
   fn from(e: LaunchError) -> Self {
     Self::Launch(e)
   }
 }
 
+impl From<PlanError> for Error {
+  fn from(e: PlanError) -> Self {
+    Self::Plan(e)
+  }
+}
+
+impl From<ShiftError> for Error {
+  fn from(e: ShiftError) -> Self {
+    Self::Shift(e)
+  }
+}
+
+impl From<OomError> for Error {
+  fn from(e: OomError) -> Self {
+    Self::Vulkan(VulkanError::Oom(e))
+  }
+}
+
+impl From<InstanceCreationError> for Error {
+  fn from(e: InstanceCreationError) -> Self {
+    Self::Vulkan(VulkanError::InstanceCreation(e))
+  }
+}
+
+impl From<DeviceCreationError> for Error {
+  fn from(e: DeviceCreationError) -> Self {
+    Self::Vulkan(VulkanError::DeviceCreation(e))
+  }
+}
+
+impl From<DeviceMemoryAllocError> for Error {
+  fn from(e: DeviceMemoryAllocError) -> Self {
+    Self::Vulkan(VulkanError::DeviceMemoryAlloc(e))
+  }
+}
+
+impl From<SubmitCommandBufferError> for Error {
+  fn from(e: SubmitCommandBufferError) -> Self {
+    Self::Vulkan(VulkanError::SubmitCommandBuffer(e))
+  }
+}
+
+impl From<FenceWaitError> for Error {
+  fn from(e: FenceWaitError) -> Self {
+    Self::Vulkan(VulkanError::FenceWait(e))
+  }
+}
+
+impl From<ReadLockError> for Error {
+  fn from(e: ReadLockError) -> Self {
+    Self::Vulkan(VulkanError::ReadLock(e))
+  }
+}
+
+impl From<WriteLockError> for Error {
+  fn from(e: WriteLockError) -> Self {
+    Self::Vulkan(VulkanError::WriteLock(e))
+  }
+}
+
 pub(crate) fn check_error(result: vkfft_sys::VkFFTResult) -> Result<()> {
-  match result.try_into() {
-    Ok(err) => Err(err),
-    Err(_) => Ok(()),
+  if result == vkfft_sys::VkFFTResult_VKFFT_SUCCESS {
+    return Ok(());
   }
+
+  Err(result.try_into().unwrap_or(Error::Unknown(result)))
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn result_codes() {
+    assert!(check_error(vkfft_sys::VkFFTResult_VKFFT_SUCCESS).is_ok());
+
+    // Every code in the header must map to its own variant rather than `Unknown`
+    for &(name, code) in vkfft_sys::VKFFT_RESULTS {
+      if code == vkfft_sys::VkFFTResult_VKFFT_SUCCESS {
+        continue;
+      }
+
+      assert!(
+        Error::try_from(code).is_ok(),
+        "{} ({}) has no error variant",
+        name,
+        code
+      );
+      assert!(check_error(code).is_err());
+    }
+
+    let unknown = vkfft_sys::VKFFT_RESULTS
+      .iter()
+      .map(|&(_, code)| code)
+      .max()
+      .unwrap()
+      + 1;
+    assert!(matches!(
+      check_error(unknown),
+      Err(Error::Unknown(code)) if code == unknown
+    ));
+  }
+
+  #[test]
+  fn context() {
+    use std::error::Error as _;
+
+    let summary = ConfigSummary {
+      size: Shape::new(&[8, 4]).unwrap(),
+      transform: Transform::R2c,
+      precision: Precision::Single,
+      direction: Direction::Both,
+      coordinate_features: 1,
+      batch_count: 1,
+    };
+
+    let error = Error::UnsupportedFftLength.during(Operation::Init, summary);
+    assert_eq!(error.kind(), ErrorKind::Configuration);
+    assert!(matches!(error.inner(), Error::UnsupportedFftLength));
+    assert!(error.source().is_some());
+    assert_eq!(
+      error.to_string(),
+      "failed to initialize the [8, 4] R2C application (Single precision, forward and inverse, \
+       1 coordinate features, 1 batches): UnsupportedFftLength"
+    );
+
+    let error = Error::FailedShaderLink.during(
+      Operation::Append {
+        direction: Direction::Inverse,
+      },
+      summary,
+    );
+    assert_eq!(error.kind(), ErrorKind::ShaderCompilation);
+    assert!(error
+      .to_string()
+      .starts_with("failed to append the inverse transform of the [8, 4] R2C application"));
+
+    assert_eq!(
+      Error::from(OomError::OutOfDeviceMemory).kind(),
+      ErrorKind::ResourceExhaustion
+    );
+    assert_eq!(
+      Error::from(SubmitCommandBufferError::DeviceLost).kind(),
+      ErrorKind::Driver
+    );
+    assert_eq!(
+      Error::from(config::BuildError::NoDevice).kind(),
+      ErrorKind::Configuration
+    );
+    assert_eq!(
+      Error::Vulkan(VulkanError::NoPhysicalDevice).kind(),
+      ErrorKind::Environment
+    );
+    assert_eq!(
+      Error::from(InstanceCreationError::IncompatibleDriver).kind(),
+      ErrorKind::Environment
+    );
+    assert!(matches!(
+      Error::from(config::BuildError::Config(ConfigError::ZeroKernelCount)),
+      Error::Config(ConfigError::ZeroKernelCount)
+    ));
+    assert_eq!(Error::Unknown(1000).kind(), ErrorKind::Driver);
+
+    let scale = |result: vk_sys::Result| VulkanError::ScalePass {
+      call: "vkAllocateDescriptorSets",
+      result: result as i32,
+    };
+    assert_eq!(
+      scale(vk_sys::ERROR_OUT_OF_POOL_MEMORY_KHR).kind(),
+      ErrorKind::ResourceExhaustion
+    );
+    assert_eq!(scale(vk_sys::ERROR_DEVICE_LOST).kind(), ErrorKind::Driver);
+    assert_eq!(
+      Error::Vulkan(scale(vk_sys::ERROR_OUT_OF_DEVICE_MEMORY)).to_string(),
+      "vkAllocateDescriptorSets failed with VkResult -2 in the scaling pass"
+    );
+  }
+}
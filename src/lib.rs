@@ -2,7 +2,11 @@
 
 pub mod app;
 pub mod config;
+pub mod correlation;
 pub mod error;
+pub mod plan;
+mod scale;
+pub mod shader;
 mod version;
 
 pub use version::*;
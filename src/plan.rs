@@ -0,0 +1,134 @@
+use std::convert::TryInto;
+
+use derive_more::{Display, Error};
+
+use crate::Version;
+
+const MAGIC: &[u8; 8] = b"VKFFTRS\0";
+const HEADER_LEN: usize = MAGIC.len() + 4 + 16 + 8;
+
+#[derive(Display, Debug, Error)]
+pub enum PlanError {
+  #[display(fmt = "the configuration was built without save_plan")]
+  NotSaved,
+  #[display(fmt = "data is not a saved plan")]
+  InvalidMagic,
+  #[display(fmt = "saved plan is truncated")]
+  Truncated,
+  #[display(fmt = "plan was saved with VkFFT {} but {} is in use", saved, current)]
+  VersionMismatch { saved: Version, current: Version },
+  #[display(
+    fmt = "plan was saved on device {:02x?} but is loaded on {:02x?}",
+    saved,
+    current
+  )]
+  DeviceMismatch { saved: [u8; 16], current: [u8; 16] },
+  #[display(fmt = "{}", _0)]
+  Io(std::io::Error),
+}
+
+/// A compiled VkFFT application tagged with the VkFFT version and device it was compiled for.
+///
+/// The layout is the magic bytes, the packed version (`u32`), the device UUID, the payload length
+/// (`u64`) and the payload as produced by VkFFT. Integers are little endian.
+pub(crate) struct SavedPlan<'a> {
+  pub(crate) version: Version,
+  pub(crate) device_uuid: [u8; 16],
+  pub(crate) payload: &'a [u8],
+}
+
+impl<'a> SavedPlan<'a> {
+  pub(crate) fn to_bytes(&self) -> Vec<u8> {
+    let mut res = Vec::with_capacity(HEADER_LEN + self.payload.len());
+    res.extend_from_slice(MAGIC);
+    res.extend_from_slice(&self.version.to_raw().to_le_bytes());
+    res.extend_from_slice(&self.device_uuid);
+    res.extend_from_slice(&(self.payload.len() as u64).to_le_bytes());
+    res.extend_from_slice(self.payload);
+    res
+  }
+
+  pub(crate) fn from_bytes(data: &'a [u8]) -> Result<Self, PlanError> {
+    if data.len() < MAGIC.len() || &data[..MAGIC.len()] != MAGIC {
+      return Err(PlanError::InvalidMagic);
+    }
+
+    if data.len() < HEADER_LEN {
+      return Err(PlanError::Truncated);
+    }
+
+    let (header, payload) = data.split_at(HEADER_LEN);
+    let version = Version::from_raw(u32::from_le_bytes(header[8..12].try_into().unwrap()));
+    let device_uuid = header[12..28].try_into().unwrap();
+    let len = u64::from_le_bytes(header[28..36].try_into().unwrap());
+
+    if payload.len() as u64 != len {
+      return Err(PlanError::Truncated);
+    }
+
+    Ok(Self {
+      version,
+      device_uuid,
+      payload,
+    })
+  }
+
+  /// Reject plans compiled by another VkFFT version or for another device
+  pub(crate) fn check(&self, version: Version, device_uuid: &[u8; 16]) -> Result<(), PlanError> {
+    if self.version != version {
+      return Err(PlanError::VersionMismatch {
+        saved: self.version,
+        current: version,
+      });
+    }
+
+    if &self.device_uuid != device_uuid {
+      return Err(PlanError::DeviceMismatch {
+        saved: self.device_uuid,
+        current: *device_uuid,
+      });
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trip() {
+    let version = Version::from_raw(10219);
+    let plan = SavedPlan {
+      version,
+      device_uuid: [7; 16],
+      payload: &[1, 2, 3, 4],
+    };
+
+    let bytes = plan.to_bytes();
+    let loaded = SavedPlan::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.version, version);
+    assert_eq!(loaded.device_uuid, [7; 16]);
+    assert_eq!(loaded.payload, &[1, 2, 3, 4]);
+    assert!(loaded.check(version, &[7; 16]).is_ok());
+
+    assert!(matches!(
+      loaded.check(Version::from_raw(10220), &[7; 16]),
+      Err(PlanError::VersionMismatch { .. })
+    ));
+    assert!(matches!(
+      loaded.check(version, &[8; 16]),
+      Err(PlanError::DeviceMismatch { .. })
+    ));
+
+    assert!(matches!(
+      SavedPlan::from_bytes(&bytes[..bytes.len() - 1]),
+      Err(PlanError::Truncated)
+    ));
+    assert!(matches!(
+      SavedPlan::from_bytes(&[0; 64]),
+      Err(PlanError::InvalidMagic)
+    ));
+  }
+}
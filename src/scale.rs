@@ -0,0 +1,527 @@
+use std::{convert::TryFrom, ptr, sync::Arc};
+
+use vk_sys as vk;
+use vulkano::{buffer::BufferAccess, device::Device, VulkanObject};
+
+use crate::{
+  config::Precision,
+  error::{Error, Result, VulkanError},
+};
+
+// Compiled from shaders/scale.comp by the build script
+const SCALE_F32: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/scale_f32.spv"));
+const SCALE_F64: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/scale_f64.spv"));
+
+/// Invocations per workgroup of the scaling shader
+const LOCAL_SIZE: u64 = 64;
+
+/// Workgroups per row of the dispatch grid, the smallest `maxComputeWorkGroupCount` Vulkan allows
+const MAX_ROW: u64 = 65535;
+
+/// One `VkBuffer` of the data to scale
+pub(crate) struct Part {
+  pub buffer: vk::Buffer,
+  /// Offset in bytes of the data within `buffer`
+  pub offset: u64,
+  /// Size in bytes of the data, starting at `offset`
+  pub size: u64,
+  /// The buffer, if it was supplied at launch rather than kept alive by the configuration
+  pub owner: Option<Arc<dyn BufferAccess>>,
+}
+
+/// The scalars of one part a dispatch scales, in the 32-bit indices of the shader
+pub(crate) struct Dispatch {
+  part: Part,
+  first: u32,
+  count: u32,
+}
+
+/// Descriptor sets of the buffers supplied at one launch, allocated from their own pool
+struct Supplied {
+  pool: vk::DescriptorPool,
+  sets: Vec<(Arc<dyn BufferAccess>, vk::DescriptorSet)>,
+}
+
+impl Supplied {
+  fn set(&self, owner: &Arc<dyn BufferAccess>) -> Option<vk::DescriptorSet> {
+    self
+      .sets
+      .iter()
+      .find(|(buffer, _)| same_buffer(buffer, owner))
+      .map(|&(_, set)| set)
+  }
+}
+
+/// Whether `a` and `b` are the same supplied buffer, rather than two holding the same handle
+fn same_buffer(a: &Arc<dyn BufferAccess>, b: &Arc<dyn BufferAccess>) -> bool {
+  Arc::as_ptr(a) as *const u8 == Arc::as_ptr(b) as *const u8
+}
+
+/// Compute pipeline multiplying the result of a transform by a constant, for the normalizations
+/// VkFFT doesn't implement
+pub(crate) struct ScalePass {
+  device: Arc<Device>,
+  precision: Precision,
+  shader: vk::ShaderModule,
+  set_layout: vk::DescriptorSetLayout,
+  pipeline_layout: vk::PipelineLayout,
+  pipeline: vk::Pipeline,
+  // Sets of the configured buffers, which the configuration keeps alive as long as the pass
+  configured_pool: vk::DescriptorPool,
+  configured: Vec<(vk::Buffer, vk::DescriptorSet)>,
+  // Sets of the buffers supplied at the last launch that supplied any, holding on to them until a
+  // launch supplies others
+  supplied: Option<Supplied>,
+}
+
+fn check(call: &'static str, result: vk::Result) -> Result<()> {
+  match result {
+    vk::SUCCESS => Ok(()),
+    _ => Err(Error::Vulkan(VulkanError::ScalePass {
+      call,
+      result: result as i32,
+    })),
+  }
+}
+
+impl ScalePass {
+  /// Create the pass and the descriptor sets of `configured`, the configured buffers it may scale
+  pub(crate) fn new(
+    device: Arc<Device>,
+    precision: Precision,
+    configured: &[vk::Buffer],
+  ) -> Result<Self> {
+    let code = match precision {
+      Precision::Double => SCALE_F64,
+      _ => SCALE_F32,
+    };
+
+    // `include_bytes!` doesn't guarantee the alignment Vulkan requires of SPIR-V
+    let code: Vec<u32> = code
+      .chunks_exact(4)
+      .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+      .collect();
+
+    let mut res = Self {
+      device,
+      precision,
+      shader: 0,
+      set_layout: 0,
+      pipeline_layout: 0,
+      pipeline: 0,
+      configured_pool: 0,
+      configured: Vec::new(),
+      supplied: None,
+    };
+
+    let vk_device = res.device.internal_object();
+    let fns = res.device.pointers();
+
+    unsafe {
+      let info = vk::ShaderModuleCreateInfo {
+        sType: vk::STRUCTURE_TYPE_SHADER_MODULE_CREATE_INFO,
+        pNext: ptr::null(),
+        flags: 0,
+        codeSize: code.len() * 4,
+        pCode: code.as_ptr(),
+      };
+      check(
+        "vkCreateShaderModule",
+        fns.CreateShaderModule(vk_device, &info, ptr::null(), &mut res.shader),
+      )?;
+
+      let binding = vk::DescriptorSetLayoutBinding {
+        binding: 0,
+        descriptorType: vk::DESCRIPTOR_TYPE_STORAGE_BUFFER,
+        descriptorCount: 1,
+        stageFlags: vk::SHADER_STAGE_COMPUTE_BIT,
+        pImmutableSamplers: ptr::null(),
+      };
+      let info = vk::DescriptorSetLayoutCreateInfo {
+        sType: vk::STRUCTURE_TYPE_DESCRIPTOR_SET_LAYOUT_CREATE_INFO,
+        pNext: ptr::null(),
+        flags: 0,
+        bindingCount: 1,
+        pBindings: &binding,
+      };
+      check(
+        "vkCreateDescriptorSetLayout",
+        fns.CreateDescriptorSetLayout(vk_device, &info, ptr::null(), &mut res.set_layout),
+      )?;
+
+      let push_constants = vk::PushConstantRange {
+        stageFlags: vk::SHADER_STAGE_COMPUTE_BIT,
+        offset: 0,
+        size: res.push_constants(0, 0, 0.0).len() as u32,
+      };
+      let info = vk::PipelineLayoutCreateInfo {
+        sType: vk::STRUCTURE_TYPE_PIPELINE_LAYOUT_CREATE_INFO,
+        pNext: ptr::null(),
+        flags: 0,
+        setLayoutCount: 1,
+        pSetLayouts: &res.set_layout,
+        pushConstantRangeCount: 1,
+        pPushConstantRanges: &push_constants,
+      };
+      check(
+        "vkCreatePipelineLayout",
+        fns.CreatePipelineLayout(vk_device, &info, ptr::null(), &mut res.pipeline_layout),
+      )?;
+
+      let info = vk::ComputePipelineCreateInfo {
+        sType: vk::STRUCTURE_TYPE_COMPUTE_PIPELINE_CREATE_INFO,
+        pNext: ptr::null(),
+        flags: 0,
+        stage: vk::PipelineShaderStageCreateInfo {
+          sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+          pNext: ptr::null(),
+          flags: 0,
+          stage: vk::SHADER_STAGE_COMPUTE_BIT,
+          module: res.shader,
+          pName: b"main\0".as_ptr() as *const _,
+          pSpecializationInfo: ptr::null(),
+        },
+        layout: res.pipeline_layout,
+        basePipelineHandle: 0,
+        basePipelineIndex: -1,
+      };
+      check(
+        "vkCreateComputePipelines",
+        fns.CreateComputePipelines(vk_device, 0, 1, &info, ptr::null(), &mut res.pipeline),
+      )?;
+
+      if !configured.is_empty() {
+        res.configured_pool = res.create_pool(configured.len())?;
+        res.configured = configured
+          .iter()
+          .map(|&buffer| Ok((buffer, res.allocate_set(res.configured_pool, buffer)?)))
+          .collect::<Result<_>>()?;
+      }
+    }
+
+    Ok(res)
+  }
+
+  /// Push constants in the layout of the shader's `Params` block
+  fn push_constants(&self, offset: u32, count: u32, factor: f64) -> Vec<u8> {
+    let mut res = Vec::with_capacity(16);
+    res.extend_from_slice(&offset.to_ne_bytes());
+    res.extend_from_slice(&count.to_ne_bytes());
+
+    match self.precision {
+      Precision::Double => res.extend_from_slice(&factor.to_ne_bytes()),
+      _ => res.extend_from_slice(&(factor as f32).to_ne_bytes()),
+    }
+
+    res
+  }
+
+  fn scalar_size(&self) -> u64 {
+    match self.precision {
+      Precision::Double => 8,
+      _ => 4,
+    }
+  }
+
+  /// A pool for `count` sets of one storage buffer each
+  unsafe fn create_pool(&self, count: usize) -> Result<vk::DescriptorPool> {
+    let size = vk::DescriptorPoolSize {
+      ty: vk::DESCRIPTOR_TYPE_STORAGE_BUFFER,
+      descriptorCount: count as u32,
+    };
+    let info = vk::DescriptorPoolCreateInfo {
+      sType: vk::STRUCTURE_TYPE_DESCRIPTOR_POOL_CREATE_INFO,
+      pNext: ptr::null(),
+      flags: 0,
+      maxSets: count as u32,
+      poolSizeCount: 1,
+      pPoolSizes: &size,
+    };
+    let mut pool = 0;
+    check(
+      "vkCreateDescriptorPool",
+      self.device.pointers().CreateDescriptorPool(
+        self.device.internal_object(),
+        &info,
+        ptr::null(),
+        &mut pool,
+      ),
+    )?;
+
+    Ok(pool)
+  }
+
+  /// Allocate a set from `pool` and write `buffer` to it
+  unsafe fn allocate_set(
+    &self,
+    pool: vk::DescriptorPool,
+    buffer: vk::Buffer,
+  ) -> Result<vk::DescriptorSet> {
+    let vk_device = self.device.internal_object();
+    let fns = self.device.pointers();
+
+    let info = vk::DescriptorSetAllocateInfo {
+      sType: vk::STRUCTURE_TYPE_DESCRIPTOR_SET_ALLOCATE_INFO,
+      pNext: ptr::null(),
+      descriptorPool: pool,
+      descriptorSetCount: 1,
+      pSetLayouts: &self.set_layout,
+    };
+    let mut set = 0;
+    check(
+      "vkAllocateDescriptorSets",
+      fns.AllocateDescriptorSets(vk_device, &info, &mut set),
+    )?;
+
+    let buffer_info = vk::DescriptorBufferInfo {
+      buffer,
+      offset: 0,
+      range: vk::WHOLE_SIZE,
+    };
+    let write = vk::WriteDescriptorSet {
+      sType: vk::STRUCTURE_TYPE_WRITE_DESCRIPTOR_SET,
+      pNext: ptr::null(),
+      dstSet: set,
+      dstBinding: 0,
+      dstArrayElement: 0,
+      descriptorCount: 1,
+      descriptorType: vk::DESCRIPTOR_TYPE_STORAGE_BUFFER,
+      pImageInfo: ptr::null(),
+      pBufferInfo: &buffer_info,
+      pTexelBufferView: ptr::null(),
+    };
+    fns.UpdateDescriptorSets(vk_device, 1, &write, 0, ptr::null());
+
+    Ok(set)
+  }
+
+  /// Make sure the supplied buffers of `dispatches` have sets, replacing those of the buffers
+  /// supplied before if any of them don't
+  unsafe fn supply(&mut self, dispatches: &[Dispatch]) -> Result<()> {
+    let mut buffers: Vec<(&Arc<dyn BufferAccess>, vk::Buffer)> = Vec::new();
+    for part in dispatches.iter().map(|d| &d.part) {
+      if let Some(owner) = &part.owner {
+        if !buffers.iter().any(|(o, _)| same_buffer(o, owner)) {
+          buffers.push((owner, part.buffer));
+        }
+      }
+    }
+
+    let cached = match &self.supplied {
+      Some(supplied) => buffers.iter().all(|(o, _)| supplied.set(o).is_some()),
+      None => buffers.is_empty(),
+    };
+    if cached {
+      return Ok(());
+    }
+
+    let pool = self.create_pool(buffers.len())?;
+    let mut sets = Vec::with_capacity(buffers.len());
+    for (owner, buffer) in buffers {
+      match self.allocate_set(pool, buffer) {
+        Ok(set) => sets.push((owner.clone(), set)),
+        Err(e) => {
+          self.destroy_pool(pool);
+          return Err(e);
+        }
+      }
+    }
+
+    // The launch contract guarantees the recordings binding the previous sets have finished
+    if let Some(previous) = self.supplied.replace(Supplied { pool, sets }) {
+      self.destroy_pool(previous.pool);
+    }
+
+    Ok(())
+  }
+
+  unsafe fn destroy_pool(&self, pool: vk::DescriptorPool) {
+    self
+      .device
+      .pointers()
+      .DestroyDescriptorPool(self.device.internal_object(), pool, ptr::null());
+  }
+
+  /// The set binding the buffer of `part`
+  fn set(&self, part: &Part) -> vk::DescriptorSet {
+    let set = match &part.owner {
+      Some(owner) => self.supplied.as_ref().and_then(|s| s.set(owner)),
+      None => self
+        .configured
+        .iter()
+        .find(|(buffer, _)| *buffer == part.buffer)
+        .map(|&(_, set)| set),
+    };
+
+    // `new` allocated sets for every configured buffer the pass scales, and `supply` for those
+    // supplied at the launch
+    set.expect("scaled buffer has no descriptor set")
+  }
+
+  /// Split the scaling of the first `len` bytes of the data in `parts` into one dispatch per part,
+  /// or `None` if the shader can't index them with 32-bit integers
+  pub(crate) fn dispatches(&self, parts: Vec<Part>, len: u64) -> Option<Vec<Dispatch>> {
+    let scalar_size = self.scalar_size();
+    let mut remaining = len;
+    let mut res = Vec::with_capacity(parts.len());
+
+    for part in parts {
+      if remaining == 0 {
+        break;
+      }
+
+      let bytes = part.size.min(remaining);
+      remaining -= bytes;
+
+      let first = part.offset / scalar_size;
+      let count = bytes / scalar_size;
+
+      // The shader indexes every scalar up to the last one of the part
+      u32::try_from(first.checked_add(count)?).ok()?;
+
+      res.push(Dispatch {
+        first: first as u32,
+        count: count as u32,
+        part,
+      });
+    }
+
+    Some(res)
+  }
+
+  /// Record the scaling of `dispatches` after the dispatches already in `command_buffer` have
+  /// written them, and make the result visible to the commands recorded after it and the host.
+  ///
+  /// Several launches supplying the same buffers may be recorded in one command buffer. As with
+  /// VkFFT, which rewrites its descriptor sets when a launch supplies other buffers, the recordings
+  /// of previous launches must have finished executing before recording a launch that supplies
+  /// different buffers: the sets of the previous ones are freed then and the buffers released.
+  pub(crate) unsafe fn record(
+    &mut self,
+    command_buffer: vk::CommandBuffer,
+    dispatches: &[Dispatch],
+    factor: f64,
+  ) -> Result<()> {
+    // Allocate the sets before recording anything, so a failure leaves the command buffer as is
+    self.supply(dispatches)?;
+
+    let fns = self.device.pointers();
+
+    let barrier = vk::MemoryBarrier {
+      sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER,
+      pNext: ptr::null(),
+      srcAccessMask: vk::ACCESS_SHADER_WRITE_BIT,
+      dstAccessMask: vk::ACCESS_SHADER_READ_BIT | vk::ACCESS_SHADER_WRITE_BIT,
+    };
+    fns.CmdPipelineBarrier(
+      command_buffer,
+      vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+      vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+      0,
+      1,
+      &barrier,
+      0,
+      ptr::null(),
+      0,
+      ptr::null(),
+    );
+
+    fns.CmdBindPipeline(
+      command_buffer,
+      vk::PIPELINE_BIND_POINT_COMPUTE,
+      self.pipeline,
+    );
+
+    for dispatch in dispatches {
+      let set = self.set(&dispatch.part);
+      fns.CmdBindDescriptorSets(
+        command_buffer,
+        vk::PIPELINE_BIND_POINT_COMPUTE,
+        self.pipeline_layout,
+        0,
+        1,
+        &set,
+        0,
+        ptr::null(),
+      );
+
+      let push_constants = self.push_constants(dispatch.first, dispatch.count, factor);
+      fns.CmdPushConstants(
+        command_buffer,
+        self.pipeline_layout,
+        vk::SHADER_STAGE_COMPUTE_BIT,
+        0,
+        push_constants.len() as u32,
+        push_constants.as_ptr() as *const _,
+      );
+
+      // At most 2^32 / LOCAL_SIZE groups, so both dimensions fit
+      let groups = (dispatch.count as u64).div_ceil(LOCAL_SIZE);
+      let row = groups.clamp(1, MAX_ROW);
+      fns.CmdDispatch(
+        command_buffer,
+        row as u32,
+        groups.div_ceil(row).max(1) as u32,
+        1,
+      );
+    }
+
+    // Make the scaled result visible to whatever reads it next, on the device or the host
+    let barrier = vk::MemoryBarrier {
+      sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER,
+      pNext: ptr::null(),
+      srcAccessMask: vk::ACCESS_SHADER_WRITE_BIT,
+      dstAccessMask: vk::ACCESS_SHADER_READ_BIT
+        | vk::ACCESS_SHADER_WRITE_BIT
+        | vk::ACCESS_TRANSFER_READ_BIT
+        | vk::ACCESS_HOST_READ_BIT,
+    };
+    fns.CmdPipelineBarrier(
+      command_buffer,
+      vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+      vk::PIPELINE_STAGE_ALL_COMMANDS_BIT | vk::PIPELINE_STAGE_HOST_BIT,
+      0,
+      1,
+      &barrier,
+      0,
+      ptr::null(),
+      0,
+      ptr::null(),
+    );
+
+    Ok(())
+  }
+}
+
+impl Drop for ScalePass {
+  fn drop(&mut self) {
+    let vk_device = self.device.internal_object();
+    let fns = self.device.pointers();
+
+    unsafe {
+      if let Some(supplied) = self.supplied.take() {
+        self.destroy_pool(supplied.pool);
+      }
+
+      if self.configured_pool != 0 {
+        self.destroy_pool(self.configured_pool);
+      }
+
+      if self.pipeline != 0 {
+        fns.DestroyPipeline(vk_device, self.pipeline, ptr::null());
+      }
+
+      if self.pipeline_layout != 0 {
+        fns.DestroyPipelineLayout(vk_device, self.pipeline_layout, ptr::null());
+      }
+
+      if self.set_layout != 0 {
+        fns.DestroyDescriptorSetLayout(vk_device, self.set_layout, ptr::null());
+      }
+
+      if self.shader != 0 {
+        fns.DestroyShaderModule(vk_device, self.shader, ptr::null());
+      }
+    }
+  }
+}
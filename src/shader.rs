@@ -0,0 +1,107 @@
+use std::{ffi::CStr, fs, io, path::Path};
+
+use crate::config::{Direction, MAX_DIMENSIONS};
+
+/// GLSL source VkFFT generated for one dispatch of a plan
+#[derive(Debug, Clone)]
+pub struct Shader {
+  /// `Forward` or `Inverse`
+  pub direction: Direction,
+  /// Axis the shader transforms
+  pub axis: usize,
+  /// Index of the shader among the uploads VkFFT split the axis into
+  pub upload: usize,
+  /// Whether this is the extra R2C decomposition pass rather than an axis upload
+  pub r2c_decomposition: bool,
+  pub source: String,
+}
+
+impl Shader {
+  /// File name used by [`ShaderSources::dump`], e.g. `forward_axis0_upload1.comp`
+  pub fn file_name(&self) -> String {
+    let direction = match self.direction {
+      Direction::Inverse => "inverse",
+      _ => "forward",
+    };
+
+    if self.r2c_decomposition {
+      format!("{}_r2c_decomposition.comp", direction)
+    } else {
+      format!("{}_axis{}_upload{}.comp", direction, self.axis, self.upload)
+    }
+  }
+}
+
+/// Shader sources of an application, kept when it is configured with `keep_shader_code`
+#[derive(Debug, Clone, Default)]
+pub struct ShaderSources {
+  shaders: Vec<Shader>,
+}
+
+impl ShaderSources {
+  /// Copy the sources VkFFT kept in the plans of an initialized application
+  pub(crate) unsafe fn collect(app: &vkfft_sys::VkFFTApplication) -> Self {
+    let mut shaders = Vec::new();
+
+    let plans = [
+      (Direction::Forward, app.localFFTPlan),
+      (Direction::Inverse, app.localFFTPlan_inverse),
+    ];
+
+    for (direction, plan) in plans.iter() {
+      let plan = match plan.as_ref() {
+        Some(plan) => plan,
+        None => continue,
+      };
+
+      let mut push = |axis, upload, r2c_decomposition, shader: &vkfft_sys::VkFFTAxis| {
+        let code = shader.specializationConstants.code0;
+        if !code.is_null() {
+          shaders.push(Shader {
+            direction: *direction,
+            axis,
+            upload,
+            r2c_decomposition,
+            source: CStr::from_ptr(code).to_string_lossy().into_owned(),
+          });
+        }
+      };
+
+      for axis in 0..MAX_DIMENSIONS {
+        for upload in 0..(plan.numAxisUploads[axis] as usize).min(4) {
+          push(axis, upload, false, &plan.axes[axis][upload]);
+        }
+      }
+
+      if plan.multiUploadR2C != 0 {
+        push(0, 0, true, &plan.R2Cdecomposition);
+      }
+    }
+
+    Self { shaders }
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Shader> {
+    self.shaders.iter()
+  }
+
+  pub fn len(&self) -> usize {
+    self.shaders.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.shaders.is_empty()
+  }
+
+  /// Write every shader to `dir`, which is created if needed
+  pub fn dump<P: AsRef<Path>>(&self, dir: P) -> io::Result<()> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    for shader in &self.shaders {
+      fs::write(dir.join(shader.file_name()), &shader.source)?;
+    }
+
+    Ok(())
+  }
+}
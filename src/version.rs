@@ -1,5 +1,12 @@
-use std::fmt::{Display, Formatter};
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use std::{
+  fmt::{Display, Formatter},
+  str::FromStr,
+};
+
+use derive_more::{Display, Error};
+
+/// A VkFFT version. Versions order by major, then minor, then patch number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Version {
   major: u32,
   minor: u32,
@@ -21,6 +28,57 @@ impl Version {
   pub fn patch(&self) -> u32 {
     self.patch
   }
+
+  /// Decode the packed representation returned by `VkFFTGetVersion`
+  pub(crate) fn from_raw(ver: u32) -> Self {
+    Self {
+      major: ver / 10000,
+      minor: ver % 10000 / 100,
+      patch: ver % 100,
+    }
+  }
+
+  pub(crate) fn to_raw(self) -> u32 {
+    self.major * 10000 + self.minor * 100 + self.patch
+  }
+}
+
+#[derive(Display, Debug, Clone, PartialEq, Eq, Error)]
+#[display(
+  fmt = "invalid VkFFT version {:?}, expected major.minor.patch",
+  version
+)]
+pub struct ParseVersionError {
+  #[error(not(source))]
+  version: String,
+}
+
+impl FromStr for Version {
+  type Err = ParseVersionError;
+
+  /// Parse a version like `1.2.21`. Minor and patch numbers are below 100, as VkFFT packs them
+  /// into two decimal digits each.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let error = || ParseVersionError {
+      version: s.to_string(),
+    };
+
+    let mut parts = s.trim().split('.').map(|part| part.parse::<u32>());
+    let (major, minor, patch) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+      (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => (major, minor, patch),
+      _ => return Err(error()),
+    };
+
+    if minor >= 100 || patch >= 100 {
+      return Err(error());
+    }
+
+    Ok(Self {
+      major,
+      minor,
+      patch,
+    })
+  }
 }
 
 impl Display for Version {
@@ -29,12 +87,37 @@ impl Display for Version {
   }
 }
 
+/// Version of VkFFT. VkFFT is header-only, so this is the version of the header vkfft-sys was
+/// compiled against.
 pub fn version() -> Version {
   let ver = unsafe { vkfft_sys::VkFFTGetVersion() };
 
-  Version {
-    major: (ver / 10000) as u32,
-    minor: (ver % 10000 / 100) as u32,
-    patch: (ver % 100) as u32,
+  Version::from_raw(ver as u32)
+}
+
+/// Version of the VkFFT header the bindings were generated from, or `None` if the build script
+/// couldn't find it in the header
+pub fn header_version() -> Option<Version> {
+  vkfft_sys::VKFFT_HEADER_VERSION.map(Version::from_raw)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_and_order() {
+    let version: Version = "1.2.21".parse().unwrap();
+    assert_eq!(version, Version::from_raw(10221));
+    assert_eq!(version.to_string().parse::<Version>(), Ok(version));
+
+    assert!("1.2".parse::<Version>().is_err());
+    assert!("1.2.3.4".parse::<Version>().is_err());
+    assert!("1.100.0".parse::<Version>().is_err());
+    assert!("1.x.0".parse::<Version>().is_err());
+
+    assert!(Version::from_raw(10221) > Version::from_raw(10219));
+    assert!(Version::from_raw(10300) > Version::from_raw(10299));
+    assert!(Version::from_raw(20000) > Version::from_raw(19999));
   }
 }
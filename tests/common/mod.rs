@@ -0,0 +1,178 @@
+#![allow(dead_code)]
+
+use std::{error::Error, pin::Pin, sync::Arc};
+
+use vkfft::{
+  app::{App, LaunchParams},
+  config::{Config, ConfigBuilder},
+  error,
+};
+use vulkano::{
+  buffer::{BufferUsage, CpuAccessibleBuffer},
+  command_buffer::{
+    sys::{Flags, UnsafeCommandBufferBuilder},
+    Kind,
+  },
+  instance::{Instance, InstanceExtensions},
+};
+
+pub use util::Context;
+
+pub const DEFAULT_BUFFER_USAGE: BufferUsage = BufferUsage {
+  storage_buffer: true,
+  transfer_source: true,
+  transfer_destination: true,
+  ..BufferUsage::none()
+};
+
+/// Run `f` with a Vulkan context. Tests calling this are marked `#[ignore]` so machines without
+/// a Vulkan device report them as ignored; run them with `cargo test -- --ignored`.
+pub fn with_context<F>(f: F)
+where
+  F: FnOnce(&mut Context) -> Result<(), Box<dyn Error>>,
+{
+  let instance = Instance::new(None, &InstanceExtensions::none(), None)
+    .unwrap_or_else(|e| panic!("no Vulkan instance: {}", e));
+  let mut context = Context::new(&instance).unwrap_or_else(|e| panic!("no Vulkan device: {}", e));
+
+  f(&mut context).unwrap();
+}
+
+pub fn buffer<T>(context: &Context, data: &[T]) -> Arc<CpuAccessibleBuffer<[T]>>
+where
+  T: 'static + Copy + Send + Sync,
+{
+  CpuAccessibleBuffer::from_iter(
+    context.device.clone(),
+    DEFAULT_BUFFER_USAGE,
+    false,
+    data.iter().copied(),
+  )
+  .unwrap()
+}
+
+/// Build a configuration for `context`, then record and run a single transform
+pub fn run<F>(context: &mut Context, configure: F, inverse: bool) -> error::Result<Pin<Box<App>>>
+where
+  F: for<'b> FnOnce(ConfigBuilder<'b>) -> ConfigBuilder<'b>,
+{
+  let config = configure(
+    Config::builder()
+      .physical_device(context.physical)
+      .device(context.device.clone())
+      .fence(&context.fence)
+      .queue(context.queue.clone())
+      .command_pool(context.pool.clone()),
+  )
+  .build()?;
+
+  let primary_cmd_buffer = context.alloc_primary_cmd_buffer()?;
+  let builder =
+    unsafe { UnsafeCommandBufferBuilder::new(&primary_cmd_buffer, Kind::primary(), Flags::None)? };
+
+  let mut params = LaunchParams::builder().command_buffer(&builder).build()?;
+  let mut app = App::new(config)?;
+  app.launch(&mut params, inverse)?;
+
+  let command_buffer = builder.build()?;
+  context.submit(command_buffer)?;
+
+  Ok(app)
+}
+
+/// Run a single transform in place on a buffer holding `input`, and read the result back
+pub fn transformed<F>(
+  context: &mut Context,
+  input: &[f32],
+  configure: F,
+  inverse: bool,
+) -> Result<Vec<f32>, Box<dyn Error>>
+where
+  F: for<'b> FnOnce(ConfigBuilder<'b>) -> ConfigBuilder<'b>,
+{
+  let data = buffer(context, input);
+  run(
+    context,
+    |builder| configure(builder).buffer(data.clone()),
+    inverse,
+  )?;
+
+  let res = data.read()?.to_vec();
+  Ok(res)
+}
+
+pub fn assert_close(actual: &[f32], expected: &[f32], tolerance: f32) {
+  assert_eq!(actual.len(), expected.len());
+
+  for (i, (a, e)) in actual.iter().zip(expected.iter()).enumerate() {
+    assert!(
+      (a - e).abs() <= tolerance * (1.0 + e.abs()),
+      "element {}: {} != {}",
+      i,
+      a,
+      e
+    );
+  }
+}
+
+/// Deterministic pseudo-random test signal in `[-0.5, 0.5)`
+pub fn signal(len: usize) -> Vec<f32> {
+  let mut state = 0x2545_f491u32;
+  (0..len)
+    .map(|_| {
+      state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+      (state >> 8) as f32 / (1 << 24) as f32 - 0.5
+    })
+    .collect()
+}
+
+/// Apply a 1D transform along `axis` of a packed array of the given size
+pub fn along_axis<F>(data: &[f64], size: [usize; 3], axis: usize, transform: F) -> Vec<f64>
+where
+  F: Fn(&[f64]) -> Vec<f64>,
+{
+  let stride = size[..axis].iter().product::<usize>();
+  let len = size[axis];
+  let mut res = data.to_vec();
+
+  for start in 0..data.len() {
+    if (start / stride) % len != 0 {
+      continue;
+    }
+
+    let line: Vec<f64> = (0..len).map(|i| data[start + i * stride]).collect();
+    for (i, v) in transform(&line).into_iter().enumerate() {
+      res[start + i * stride] = v;
+    }
+  }
+
+  res
+}
+
+pub type Complex = (f64, f64);
+
+/// Direct 2D DFT of packed complex data, with the sign of the exponent given by `sign`
+pub fn dft_2d(data: &[Complex], size: [usize; 2], sign: f64) -> Vec<Complex> {
+  let [w, h] = size;
+  let mut res = vec![(0.0, 0.0); w * h];
+
+  for v in 0..h {
+    for u in 0..w {
+      let mut sum = (0.0, 0.0);
+      for y in 0..h {
+        for x in 0..w {
+          let phase = sign
+            * 2.0
+            * std::f64::consts::PI
+            * ((u * x) as f64 / w as f64 + (v * y) as f64 / h as f64);
+          let (re, im) = data[x + y * w];
+          sum.0 += re * phase.cos() - im * phase.sin();
+          sum.1 += re * phase.sin() + im * phase.cos();
+        }
+      }
+      res[u + v * w] = sum;
+    }
+  }
+
+  res
+}
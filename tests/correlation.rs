@@ -0,0 +1,163 @@
+mod common;
+
+use common::{assert_close, buffer, dft_2d, run, signal, with_context, Complex};
+
+const N: usize = 64;
+
+/// Circular cross-correlation, `c[s] = sum_n x[n + s] * k[n]`
+fn cross_correlate(x: &[f32], k: &[f32]) -> Vec<f32> {
+  (0..x.len())
+    .map(|s| {
+      (0..x.len())
+        .map(|n| x[(n + s) % x.len()] as f64 * k[n] as f64)
+        .sum::<f64>() as f32
+    })
+    .collect()
+}
+
+/// Store real values in the padded layout of an in-place R2C transform
+fn padded(data: &[f32]) -> Vec<f32> {
+  let mut res = data.to_vec();
+  res.resize(2 * (data.len() / 2 + 1), 0.0);
+  res
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn cross_correlation_peak() {
+  let shift = 13;
+
+  with_context(|context| {
+    let template = signal(N);
+    let shifted: Vec<f32> = (0..N).map(|i| template[(i + N - shift) % N]).collect();
+
+    let kernel = buffer(context, &padded(&template));
+    let data = buffer(context, &padded(&shifted));
+
+    // Transform the template into the kernel spectrum
+    run(
+      context,
+      |builder| {
+        builder
+          .dim(&[N as u64])
+          .r2c()
+          .kernel_convolution()
+          .buffer(kernel.clone())
+      },
+      false,
+    )?;
+
+    run(
+      context,
+      |builder| {
+        builder
+          .dim(&[N as u64])
+          .r2c()
+          .cross_correlation()
+          .normalize()
+          .kernel(kernel.clone())
+          .buffer(data.clone())
+      },
+      false,
+    )?;
+
+    let expected = cross_correlate(&shifted, &template);
+    let actual = data.read()?[..N].to_vec();
+    assert_close(&actual, &expected, 1e-3);
+
+    let peak = (0..N)
+      .max_by(|&a, &b| actual[a].partial_cmp(&actual[b]).unwrap())
+      .unwrap();
+    assert_eq!(peak, shift);
+
+    Ok(())
+  });
+}
+
+/// `ifft(X conj(K) / |X conj(K)|)`, normalized
+fn phase_correlate(x: &[f32], k: &[f32], size: [usize; 2]) -> Vec<f32> {
+  let complex = |d: &[f32]| d.iter().map(|&v| (v as f64, 0.0)).collect::<Vec<_>>();
+  let fx = dft_2d(&complex(x), size, -1.0);
+  let fk = dft_2d(&complex(k), size, -1.0);
+
+  let product: Vec<Complex> = fx
+    .iter()
+    .zip(fk.iter())
+    .map(|(&(a, b), &(c, d))| {
+      let (re, im) = (a * c + b * d, b * c - a * d);
+      let norm = (re * re + im * im).sqrt().max(1e-30);
+      (re / norm, im / norm)
+    })
+    .collect();
+
+  let len = (size[0] * size[1]) as f64;
+  dft_2d(&product, size, 1.0)
+    .into_iter()
+    .map(|(re, _)| (re / len) as f32)
+    .collect()
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn phase_correlation_peak() {
+  const W: usize = 16;
+  const H: usize = 16;
+  let (dx, dy) = (5, 3);
+
+  with_context(|context| {
+    let template = signal(W * H);
+    let shifted: Vec<f32> = (0..W * H)
+      .map(|i| {
+        let (x, y) = (i % W, i / W);
+        template[(x + W - dx) % W + ((y + H - dy) % H) * W]
+      })
+      .collect();
+
+    // Rows of in-place R2C data are padded to W + 2 values
+    let pad = |data: &[f32]| -> Vec<f32> { data.chunks(W).flat_map(padded).collect() };
+
+    let kernel = buffer(context, &pad(&template));
+    let data = buffer(context, &pad(&shifted));
+
+    run(
+      context,
+      |builder| {
+        builder
+          .dim(&[W as u64, H as u64])
+          .r2c()
+          .kernel_convolution()
+          .buffer(kernel.clone())
+      },
+      false,
+    )?;
+
+    let app = run(
+      context,
+      |builder| {
+        builder
+          .dim(&[W as u64, H as u64])
+          .r2c()
+          .phase_correlation()
+          .normalize()
+          .kernel(kernel.clone())
+          .buffer(data.clone())
+      },
+      false,
+    )?;
+
+    let surface = data.read()?.to_vec();
+    let actual: Vec<f32> = surface
+      .chunks(W + 2)
+      .flat_map(|row| row[..W].to_vec())
+      .collect();
+    assert_close(&actual, &phase_correlate(&shifted, &template, [W, H]), 1e-3);
+
+    let shift = app.phase_correlation_peak(&surface)?;
+    assert_eq!(shift.integer[..2], [dx as i64, dy as i64]);
+    assert!(shift.integer[2..].iter().all(|&v| v == 0));
+    assert!((shift.subpixel[0] - dx as f64).abs() < 0.5);
+    assert!((shift.subpixel[1] - dy as f64).abs() < 0.5);
+
+    Ok(())
+  });
+}
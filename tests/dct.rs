@@ -0,0 +1,111 @@
+mod common;
+
+use std::f64::consts::PI;
+
+use common::{along_axis, assert_close, signal, transformed, with_context};
+use vkfft::config::Transform;
+
+fn dct1(x: &[f64]) -> Vec<f64> {
+  let n = x.len();
+  (0..n)
+    .map(|k| {
+      let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+      x[0]
+        + sign * x[n - 1]
+        + 2.0
+          * (1..n - 1)
+            .map(|j| x[j] * (PI * (j * k) as f64 / (n - 1) as f64).cos())
+            .sum::<f64>()
+    })
+    .collect()
+}
+
+fn dct2(x: &[f64]) -> Vec<f64> {
+  let n = x.len() as f64;
+  (0..x.len())
+    .map(|k| {
+      2.0
+        * x
+          .iter()
+          .enumerate()
+          .map(|(j, v)| v * (PI * (j as f64 + 0.5) * k as f64 / n).cos())
+          .sum::<f64>()
+    })
+    .collect()
+}
+
+fn dct3(x: &[f64]) -> Vec<f64> {
+  let n = x.len() as f64;
+  (0..x.len())
+    .map(|k| {
+      x[0]
+        + 2.0
+          * x
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(j, v)| v * (PI * j as f64 * (k as f64 + 0.5) / n).cos())
+            .sum::<f64>()
+    })
+    .collect()
+}
+
+fn dct4(x: &[f64]) -> Vec<f64> {
+  let n = x.len() as f64;
+  (0..x.len())
+    .map(|k| {
+      2.0
+        * x
+          .iter()
+          .enumerate()
+          .map(|(j, v)| v * (PI * (j as f64 + 0.5) * (k as f64 + 0.5) / n).cos())
+          .sum::<f64>()
+    })
+    .collect()
+}
+
+fn check(transform: Transform, reference: fn(&[f64]) -> Vec<f64>) {
+  with_context(|context| {
+    let input = signal(16 * 8);
+    let actual = transformed(
+      context,
+      &input,
+      |builder| builder.dim(&[16, 8]).transform(transform),
+      false,
+    )?;
+
+    let dims = [16, 8, 1];
+    let expected: Vec<f64> = input.iter().map(|&v| v as f64).collect();
+    let expected = along_axis(&expected, dims, 0, reference);
+    let expected = along_axis(&expected, dims, 1, reference);
+    let expected: Vec<f32> = expected.into_iter().map(|v| v as f32).collect();
+
+    assert_close(&actual, &expected, 1e-3);
+
+    Ok(())
+  });
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn dct_1() {
+  check(Transform::Dct1, dct1);
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn dct_2() {
+  check(Transform::Dct2, dct2);
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn dct_3() {
+  check(Transform::Dct3, dct3);
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn dct_4() {
+  check(Transform::Dct4, dct4);
+}
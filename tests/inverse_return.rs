@@ -0,0 +1,79 @@
+mod common;
+
+use common::{assert_close, buffer, run, signal, with_context};
+use vkfft::{app::LaunchError, error::Error};
+
+const N: usize = 32;
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn inverse_returns_to_input() {
+  with_context(|context| {
+    let real = signal(N);
+    let input = buffer(context, &real);
+    let spectrum = buffer(context, &[0.0f32; 2 * (N / 2 + 1)]);
+
+    run(
+      context,
+      |builder| {
+        builder
+          .dim(&[N as u64])
+          .r2c()
+          .inverse_return_to_input_buffer()
+          .input_buffer(input.clone())
+          .buffer(spectrum.clone())
+      },
+      false,
+    )?;
+
+    // Clear the input so only the inverse can restore it
+    let cleared = buffer(context, &[0.0f32; N]);
+
+    run(
+      context,
+      |builder| {
+        builder
+          .dim(&[N as u64])
+          .r2c()
+          .normalize()
+          .inverse_return_to_input_buffer()
+          .input_buffer(cleared.clone())
+          .buffer(spectrum.clone())
+      },
+      true,
+    )?;
+
+    assert_close(&cleared.read()?, &real, 1e-4);
+
+    Ok(())
+  });
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn inverse_requires_input_buffer() {
+  with_context(|context| {
+    let spectrum = buffer(context, &[0.0f32; 2 * (N / 2 + 1)]);
+
+    // Only the size of the input buffer is configured, and launches don't supply one
+    let res = run(
+      context,
+      |builder| {
+        builder
+          .dim(&[N as u64])
+          .r2c()
+          .inverse_return_to_input_buffer()
+          .input_buffer((N * 4) as u64)
+          .buffer(spectrum.clone())
+      },
+      true,
+    );
+
+    assert!(matches!(
+      res.err(),
+      Some(Error::Launch(LaunchError::NoInputBuffer))
+    ));
+
+    Ok(())
+  });
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use common::{assert_close, buffer, dft_2d, run, signal, transformed, with_context, Complex};
+use vkfft::{
+  app::{App, LaunchParams},
+  config::{Config, Normalization},
+};
+use vulkano::command_buffer::{
+  sys::{Flags, UnsafeCommandBufferBuilder},
+  Kind,
+};
+
+const W: usize = 8;
+const H: usize = 4;
+
+/// Factors a forward and an inverse transform over `len` values are expected to be scaled by
+fn factors(normalization: Normalization, len: usize) -> (f64, f64) {
+  let len = len as f64;
+
+  match normalization {
+    Normalization::None => (1.0, 1.0),
+    Normalization::Inverse => (1.0, 1.0 / len),
+    Normalization::Orthonormal => (1.0 / len.sqrt(), 1.0 / len.sqrt()),
+    Normalization::Custom(factor) => (factor, factor),
+  }
+}
+
+fn check(normalization: Normalization) {
+  let (forward, inverse) = factors(normalization, W * H);
+
+  with_context(|context| {
+    let input = signal(2 * W * H);
+    let complex: Vec<Complex> = input
+      .chunks(2)
+      .map(|c| (c[0] as f64, c[1] as f64))
+      .collect();
+
+    for &(inverse_launch, sign, factor) in [(false, -1.0, forward), (true, 1.0, inverse)].iter() {
+      let actual = transformed(
+        context,
+        &input,
+        |builder| {
+          builder
+            .dim(&[W as u64, H as u64])
+            .normalization(normalization)
+        },
+        inverse_launch,
+      )?;
+
+      let expected: Vec<f32> = dft_2d(&complex, [W, H], sign)
+        .into_iter()
+        .flat_map(|(re, im)| vec![(re * factor) as f32, (im * factor) as f32])
+        .collect();
+      assert_close(&actual, &expected, 1e-3);
+    }
+
+    Ok(())
+  });
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn no_normalization() {
+  check(Normalization::None);
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn inverse_normalization() {
+  check(Normalization::Inverse);
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn orthonormal_normalization() {
+  check(Normalization::Orthonormal);
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn custom_normalization() {
+  check(Normalization::Custom(0.25));
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn orthonormal_round_trip() {
+  with_context(|context| {
+    let input = signal(2 * W * H);
+    let data = buffer(context, &input);
+
+    for &inverse in [false, true].iter() {
+      run(
+        context,
+        |builder| {
+          builder
+            .dim(&[W as u64, H as u64])
+            .normalization(Normalization::Orthonormal)
+            .buffer(data.clone())
+        },
+        inverse,
+      )?;
+    }
+
+    assert_close(&data.read()?, &input, 1e-4);
+
+    Ok(())
+  });
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn round_trip_in_one_command_buffer() {
+  // Both directions run the scaling pass, so the inverse reads the scaled forward result
+  let factor = 0.5;
+
+  with_context(|context| {
+    let input = signal(2 * W * H);
+    let data = buffer(context, &input);
+
+    let config = Config::builder()
+      .physical_device(context.physical)
+      .device(context.device.clone())
+      .fence(&context.fence)
+      .queue(context.queue.clone())
+      .command_pool(context.pool.clone())
+      .dim(&[W as u64, H as u64])
+      .normalization(Normalization::Custom(factor))
+      .buffer(data.clone())
+      .build()?;
+
+    let primary_cmd_buffer = context.alloc_primary_cmd_buffer()?;
+    let builder = unsafe {
+      UnsafeCommandBufferBuilder::new(&primary_cmd_buffer, Kind::primary(), Flags::None)?
+    };
+
+    let mut params = LaunchParams::builder().command_buffer(&builder).build()?;
+    let mut app = App::new(config)?;
+    app.forward(&mut params)?;
+    app.inverse(&mut params)?;
+
+    context.submit(builder.build()?)?;
+
+    let scale = (W * H) as f32 * (factor * factor) as f32;
+    let expected: Vec<f32> = input.iter().map(|v| v * scale).collect();
+    assert_close(&data.read()?, &expected, 1e-3);
+
+    Ok(())
+  });
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::{assert_close, dft_2d, signal, transformed, with_context, Complex};
+use vkfft::config::PrunedFft;
+
+const W: usize = 16;
+const H: usize = 8;
+
+/// Interleave complex values into the layout of a C2C buffer
+fn interleave(data: &[Complex]) -> Vec<f32> {
+  data
+    .iter()
+    .flat_map(|&(re, im)| vec![re as f32, im as f32])
+    .collect()
+}
+
+fn complex_signal() -> Vec<Complex> {
+  signal(2 * W * H)
+    .chunks(2)
+    .map(|c| (c[0] as f64, c[1] as f64))
+    .collect()
+}
+
+/// Whether `i` is among the `len` values from `start` of an axis of size `n`
+fn kept(i: usize, start: usize, len: usize, n: usize) -> bool {
+  (i + n - start) % n < len
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn nonzero_input() {
+  // Rows 0..3 and columns 0..5 may be non-zero
+  let keep = |x, y| kept(x, 0, 5, W) && kept(y, 0, 3, H);
+
+  with_context(|context| {
+    // The buffer holds values outside of the kept region, which must be read as zeros
+    let input = complex_signal();
+    let actual = transformed(
+      context,
+      &interleave(&input),
+      |builder| {
+        builder
+          .dim(&[W as u64, H as u64])
+          .pruned(PrunedFft::nonzero_input().keep(0, 0, 5).keep(1, 0, 3))
+      },
+      false,
+    )?;
+
+    let zeroed: Vec<Complex> = input
+      .iter()
+      .enumerate()
+      .map(|(i, &v)| if keep(i % W, i / W) { v } else { (0.0, 0.0) })
+      .collect();
+    let expected = interleave(&dft_2d(&zeroed, [W, H], -1.0));
+    assert_close(&actual, &expected, 1e-3);
+
+    Ok(())
+  });
+}
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn needed_output() {
+  // Low frequencies, wrapping around the end of each axis
+  let keep = |x, y| kept(x, W - 3, 7, W) && kept(y, H - 2, 5, H);
+  let pruned = || {
+    PrunedFft::needed_output()
+      .keep(0, W as u64 - 3, 7)
+      .keep(1, H as u64 - 2, 5)
+  };
+
+  with_context(|context| {
+    let input = complex_signal();
+
+    // The forward transform only has to produce the kept frequencies
+    let actual = transformed(
+      context,
+      &interleave(&input),
+      |builder| builder.dim(&[W as u64, H as u64]).pruned(pruned()),
+      false,
+    )?;
+
+    let spectrum = dft_2d(&input, [W, H], -1.0);
+    let expected = interleave(&spectrum);
+    for i in (0..W * H).filter(|&i| keep(i % W, i / W)) {
+      assert_close(&actual[2 * i..2 * i + 2], &expected[2 * i..2 * i + 2], 1e-3);
+    }
+
+    // The inverse reads the skipped frequencies as zeros, whatever the buffer holds there
+    let actual = transformed(
+      context,
+      &interleave(&input),
+      |builder| builder.dim(&[W as u64, H as u64]).pruned(pruned()),
+      true,
+    )?;
+
+    let zeroed: Vec<Complex> = input
+      .iter()
+      .enumerate()
+      .map(|(i, &v)| if keep(i % W, i / W) { v } else { (0.0, 0.0) })
+      .collect();
+    let expected = interleave(&dft_2d(&zeroed, [W, H], 1.0));
+    assert_close(&actual, &expected, 1e-3);
+
+    Ok(())
+  });
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::{buffer, run, with_context};
+
+#[test]
+#[ignore = "requires a Vulkan device"]
+fn keeps_shader_code() {
+  with_context(|context| {
+    let data = buffer(context, &[0.0f32; 2 * 64]);
+
+    let app = run(
+      context,
+      |builder| builder.dim(&[64]).keep_shader_code().buffer(data.clone()),
+      false,
+    )?;
+
+    let sources = app.shader_sources().expect("shader sources were kept");
+    assert!(!sources.is_empty());
+    assert!(sources
+      .iter()
+      .all(|shader| shader.source.contains("void main")));
+
+    let dir = std::env::temp_dir().join("vkfft-shaders");
+    sources.dump(&dir)?;
+    for shader in sources.iter() {
+      assert!(dir.join(shader.file_name()).exists());
+    }
+
+    Ok(())
+  });
+}